@@ -9,6 +9,7 @@ pub mod config;
 mod errors_ui;
 pub mod game_updates;
 mod grid;
+mod import_ui;
 pub mod library;
 mod logs_ui;
 pub mod persistable;
@@ -16,6 +17,7 @@ pub mod settings;
 mod update_ui;
 
 mod downloads;
+mod downloads_ui;
 mod fetch;
 mod runtime;
 mod state;
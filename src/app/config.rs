@@ -3,11 +3,10 @@ use lazy_static::lazy_static;
 use reqwest::cookie::{CookieStore, Jar};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use url::Url;
 
-const BASE_URL: &str = "https://f95zone.to/";
-const LOGIN_URL: &str = "https://f95zone.to/login/login";
+const LOGIN_PATH: &str = "login/login";
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppConfig {
@@ -21,6 +20,8 @@ impl Persistable for AppConfig {}
 
 lazy_static! {
     pub static ref APP_CONFIG: RwLock<AppConfig> = RwLock::new(AppConfig::default());
+    // Orders saves from the UI and from cookie refreshes on network threads
+    static ref SAVE_LOCK: Mutex<()> = Mutex::new(());
 }
 
 fn config_file_path() -> PathBuf {
@@ -155,10 +156,20 @@ pub fn load_config_from_disk() {
     }
 }
 
+/// Written to a temporary file first and renamed over the old one, so a crash mid-write can't
+/// lose the session.
 pub fn save_config_to_disk() {
     let path = config_file_path();
+    let _guard = SAVE_LOCK.lock().unwrap();
+    // Snapshot under the save lock: the latest config is always the one written last
     let cfg = APP_CONFIG.read().unwrap().clone();
-    if let Err(e) = cfg.save_to_file(&path) {
+    let mut tmp = path.clone().into_os_string();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    if let Err(e) = cfg
+        .save_to_file(&tmp)
+        .and_then(|_| std::fs::rename(&tmp, &path))
+    {
         log::error!(
             "Failed to save app_config to {}: {}",
             path.to_string_lossy(),
@@ -172,19 +183,18 @@ pub fn save_config_to_disk() {
 /// Perform login against f95zone and persist cookies into app_config.json.
 /// On success, APP_CONFIG.cookies will contain a ready-to-use "Cookie" header string.
 pub async fn login_and_store(login: String, password: String) -> Result<(), String> {
-    let base_url = Url::parse(BASE_URL).map_err(|e| format!("invalid base URL: {e}"))?;
+    let endpoints = crate::net::endpoints::current();
+    let base_url = Url::parse(&endpoints.base).map_err(|e| format!("invalid base URL: {e}"))?;
+    let login_url = endpoints.url(LOGIN_PATH);
     let jar = Arc::new(Jar::default());
-    let client = reqwest::Client::builder()
-        .user_agent(
-            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/125.0.0.0 Safari/537.36"
-        )
+    let client = crate::net::builder()
         .cookie_provider(jar.clone())
         .build()
         .map_err(|e| format!("failed to build login client: {e}"))?;
 
     // Fetch XenForo login token into a cookie-aware client session.
     let page_resp = client
-        .get(LOGIN_URL)
+        .get(&login_url)
         .send()
         .await
         .map_err(|e| format!("failed to fetch login page: {e}"))?;
@@ -205,14 +215,14 @@ pub async fn login_and_store(login: String, password: String) -> Result<(), Stri
     form.insert("password_confirm".to_string(), "".to_string());
     form.insert("additional_security".to_string(), "".to_string());
     form.insert("remember".to_string(), "1".to_string());
-    form.insert("_xfRedirect".to_string(), "https://f95zone.to/".to_string());
+    form.insert("_xfRedirect".to_string(), endpoints.base.clone());
     form.insert("website_code".to_string(), "".to_string());
     form.insert("_xfToken".to_string(), csrf_token.to_string());
 
     let resp = client
-        .post(LOGIN_URL)
+        .post(&login_url)
         .header("Content-Type", "application/x-www-form-urlencoded")
-        .header("Referer", LOGIN_URL)
+        .header("Referer", &login_url)
         .form(&form)
         .send()
         .await
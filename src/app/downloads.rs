@@ -1,16 +1,129 @@
-use std::sync::mpsc;
+use std::path::PathBuf;
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
 
 use eframe::egui;
 
-use crate::game_download::{GameDownloadStatus, Progress};
+use crate::game_download::throttle::DownloadThrottle;
+use crate::game_download::{CaptchaPrompt, GameDownloadStatus, Progress};
+use crate::parser::game_info::PlatformDownloads;
 use crate::parser::game_info::link::DownloadLink;
 use crate::ui_constants::download::{DOWNLOAD_WEIGHT, UNZIP_WEIGHT};
+use crate::views::cards::items::card::DownloadPrompts;
 
 pub(super) struct DownloadState {
     pub(super) rx: mpsc::Receiver<GameDownloadStatus>,
     pub(super) title: String,
+    /// Thread version at start; names the folder in the archive library
+    pub(super) version: String,
     pub(super) progress: Option<Progress>,
     pub(super) link_choices: Option<Vec<DownloadLink>>,
+    pub(super) captcha: Option<CaptchaPrompt>,
+    pub(super) platform_choices: Option<Vec<PlatformDownloads>>,
+    /// Game folder being repaired: on completion only its broken files are restored
+    pub(super) repair: Option<PathBuf>,
+    /// Bytes, speed, hosting and speed cap shown in the downloads window
+    pub(super) stats: TransferStats,
+}
+
+impl DownloadState {
+    /// A download that was just started and hasn't reported anything yet
+    pub(super) fn new(
+        rx: mpsc::Receiver<GameDownloadStatus>,
+        title: String,
+        version: String,
+    ) -> Self {
+        Self {
+            rx,
+            title,
+            version,
+            progress: Some(Progress::Unknown),
+            link_choices: None,
+            captcha: None,
+            platform_choices: None,
+            repair: None,
+            stats: TransferStats::default(),
+        }
+    }
+
+    /// Choices and captcha the card shows for this download
+    pub(super) fn prompts(&self) -> DownloadPrompts<'_> {
+        DownloadPrompts {
+            link_choices: self.link_choices.as_deref(),
+            captcha: self.captcha.as_ref(),
+            platform_choices: self.platform_choices.as_deref(),
+        }
+    }
+}
+
+/// Minimum time between speed samples; shorter windows make the speed jump around
+const SPEED_SAMPLE: Duration = Duration::from_millis(500);
+/// Weight of the newest sample in the smoothed speed
+const SPEED_SMOOTHING: f64 = 0.3;
+
+pub(super) struct TransferStats {
+    pub(super) started: Instant,
+    pub(super) hosting: Option<String>,
+    /// Speed cap of the running transfer, once a mirror has started
+    pub(super) throttle: Option<Arc<DownloadThrottle>>,
+    pub(super) done: u64,
+    pub(super) total: u64,
+    pub(super) unzipping: bool,
+    /// Smoothed download speed in bytes per second
+    speed: f64,
+    last_sample: Option<(Instant, u64)>,
+}
+
+impl Default for TransferStats {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            hosting: None,
+            throttle: None,
+            done: 0,
+            total: 0,
+            unzipping: false,
+            speed: 0.0,
+            last_sample: None,
+        }
+    }
+}
+
+impl TransferStats {
+    pub(super) fn record(&mut self, done: u64, total: u64, now: Instant) {
+        self.done = done;
+        self.total = total;
+        match self.last_sample {
+            None => self.last_sample = Some((now, done)),
+            Some((at, prev)) => {
+                let elapsed = now.duration_since(at);
+                if elapsed < SPEED_SAMPLE {
+                    return;
+                }
+                let current = done.saturating_sub(prev) as f64 / elapsed.as_secs_f64();
+                self.speed = if self.speed == 0.0 {
+                    current
+                } else {
+                    self.speed + SPEED_SMOOTHING * (current - self.speed)
+                };
+                self.last_sample = Some((now, done));
+            }
+        }
+    }
+
+    /// Bytes per second while the download runs
+    pub(super) fn speed(&self) -> Option<f64> {
+        (!self.unzipping && self.speed > 0.0).then_some(self.speed)
+    }
+
+    pub(super) fn eta(&self) -> Option<Duration> {
+        let speed = self.speed()?;
+        if self.total == 0 {
+            return None;
+        }
+        let left = self.total.saturating_sub(self.done) as f64;
+        Some(Duration::from_secs_f64(left / speed))
+    }
 }
 
 /// Helper function to handle progress updates uniformly.
@@ -35,6 +148,9 @@ fn handle_progress(
             ctx.request_repaint();
         }
         Progress::Error(e) => {
+            if state.repair.is_some() {
+                crate::game_download::integrity::repair_failed(id, e.clone());
+            }
             if phase == "Unzip" {
                 log::error!("error during {}: {e}", phase);
             }
@@ -58,13 +174,50 @@ impl super::NoLagApp {
         let mut need_lib_refresh = false;
         for (id, state) in self.downloads.iter_mut() {
             while let Ok(status) = state.rx.try_recv() {
+                // Any other status means a mirror got through (or everything failed)
+                if !matches!(status, GameDownloadStatus::CaptchaRequired(_)) {
+                    state.captcha = None;
+                }
                 match status {
+                    GameDownloadStatus::Started { hosting, throttle } => {
+                        state.stats.hosting = Some(hosting);
+                        state.stats.throttle = Some(throttle);
+                        ctx.request_repaint();
+                    }
+                    GameDownloadStatus::Transferred { done, total } => {
+                        state.stats.record(done, total, Instant::now());
+                        if total > 0 {
+                            let progress = Progress::Pending(done as f32 / total as f32);
+                            handle_progress(
+                                state,
+                                progress,
+                                ctx,
+                                *id,
+                                0.0,
+                                DOWNLOAD_WEIGHT,
+                                "Download",
+                            );
+                        } else if matches!(state.progress, Some(Progress::Paused)) {
+                            // Resumed after the download window; the size is unknown
+                            state.progress = Some(Progress::Unknown);
+                        }
+                    }
                     GameDownloadStatus::SelectLinks(links) => {
                         // Ask UI to let user select a link; keep progress unknown to show "awaiting" state
                         state.link_choices = Some(links);
                         state.progress = Some(Progress::Unknown);
                         ctx.request_repaint();
                     }
+                    GameDownloadStatus::SelectPlatform(choices) => {
+                        state.platform_choices = Some(choices);
+                        state.progress = Some(Progress::Unknown);
+                        ctx.request_repaint();
+                    }
+                    GameDownloadStatus::CaptchaRequired(prompt) => {
+                        state.captcha = Some(prompt);
+                        state.progress = Some(Progress::Unknown);
+                        ctx.request_repaint();
+                    }
                     GameDownloadStatus::Downloading(progress) => {
                         handle_progress(
                             state,
@@ -76,7 +229,10 @@ impl super::NoLagApp {
                             "Download",
                         );
                     }
+                    // Consumed by the download task, never forwarded
+                    GameDownloadStatus::HostingFailed => {}
                     GameDownloadStatus::Unzipping(progress) => {
+                        state.stats.unzipping = true;
                         handle_progress(
                             state,
                             progress,
@@ -87,12 +243,29 @@ impl super::NoLagApp {
                             "Unzip",
                         );
                     }
-                    GameDownloadStatus::Completed { dest_dir, exe_path } => {
+                    GameDownloadStatus::Completed {
+                        dest_dir,
+                        exe_path,
+                        archive,
+                    } => {
                         state.progress = None;
-                        // Persist installed game info
-                        super::settings::record_downloaded_game(*id, dest_dir, exe_path);
-                        // Mark to refresh Library snapshot after we finish iterating (avoid borrow conflicts)
-                        need_lib_refresh = true;
+                        super::downloads_ui::record_completed(*id, state, &dest_dir);
+                        if let Some(archive) = archive {
+                            crate::game_download::archive_store::start_store(
+                                *id,
+                                state.version.clone(),
+                                archive,
+                            );
+                        }
+                        if let Some(game_dir) = state.repair.take() {
+                            // Repair: copy broken files from the fresh extraction, keep the install
+                            crate::game_download::integrity::start_restore(*id, dest_dir, game_dir);
+                        } else {
+                            // Persist installed game info
+                            super::settings::record_downloaded_game(*id, dest_dir, exe_path);
+                            // Mark to refresh Library snapshot after we finish iterating (avoid borrow conflicts)
+                            need_lib_refresh = true;
+                        }
                         // Remove after loop to avoid borrow conflicts
                         done.push(*id);
                         ctx.request_repaint();
@@ -109,3 +282,33 @@ impl super::NoLagApp {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_stats_speed_and_eta() {
+        let start = Instant::now();
+        let mut stats = TransferStats::default();
+        stats.record(0, 10_000, start);
+        assert_eq!(stats.speed(), None);
+        // Samples closer than SPEED_SAMPLE don't change the speed
+        stats.record(500, 10_000, start + Duration::from_millis(100));
+        assert_eq!(stats.speed(), None);
+        assert_eq!(stats.done, 500);
+
+        stats.record(1_000, 10_000, start + Duration::from_secs(1));
+        assert_eq!(stats.speed(), Some(1_000.0));
+        assert_eq!(stats.eta(), Some(Duration::from_secs(9)));
+
+        // 3000 B/s is smoothed towards, not jumped to
+        stats.record(4_000, 10_000, start + Duration::from_secs(2));
+        let speed = stats.speed().unwrap();
+        assert!((speed - 1_600.0).abs() < 1e-6, "{speed}");
+
+        stats.unzipping = true;
+        assert_eq!(stats.speed(), None);
+        assert_eq!(stats.eta(), None);
+    }
+}
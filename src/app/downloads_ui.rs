@@ -0,0 +1,515 @@
+// Downloads viewport (separate OS window): active, failed and queued transfers with speed and
+// ETA, plus the history of completed downloads kept in download_history.json.
+
+use eframe::egui;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use super::downloads::DownloadState;
+use super::persistable::Persistable;
+use crate::game_download::{Progress, integrity};
+use crate::localization::{translate, translate_with};
+use crate::parser::game_info::ThreadId;
+use crate::parser::game_info::link::format_size;
+
+/// Oldest entries are dropped beyond this
+const MAX_HISTORY: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HistoryEntry {
+    thread_id: u64,
+    title: String,
+    version: String,
+    /// None for installs from a local archive
+    hosting: Option<String>,
+    /// Archive size in bytes (0 when nothing was downloaded)
+    size: u64,
+    duration_secs: u64,
+    /// SHA-256 of the archive, from the install manifest
+    sha256: Option<String>,
+    /// Unix timestamp (seconds)
+    finished_at: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DownloadHistory {
+    entries: Vec<HistoryEntry>,
+}
+
+impl Persistable for DownloadHistory {}
+
+impl DownloadHistory {
+    fn push(&mut self, entry: HistoryEntry) {
+        self.entries.push(entry);
+        if self.entries.len() > MAX_HISTORY {
+            let extra = self.entries.len() - MAX_HISTORY;
+            self.entries.drain(..extra);
+        }
+    }
+}
+
+fn history_path() -> PathBuf {
+    PathBuf::from("download_history.json")
+}
+
+fn load_history() -> DownloadHistory {
+    let path = history_path();
+    if !path.exists() {
+        return DownloadHistory::default();
+    }
+    DownloadHistory::load_from_file(&path).unwrap_or_else(|e| {
+        log::warn!("Failed to load {}: {}", path.display(), e);
+        DownloadHistory::default()
+    })
+}
+
+fn save_history(history: &DownloadHistory) {
+    let path = history_path();
+    if let Err(e) = history.save_to_file(&path) {
+        log::error!("Failed to save {}: {}", path.display(), e);
+    }
+}
+
+lazy_static! {
+    static ref DOWNLOADS_OPEN: RwLock<bool> = RwLock::new(false);
+    static ref HISTORY: Mutex<DownloadHistory> = Mutex::new(load_history());
+}
+
+pub(super) fn open_downloads() {
+    if let Ok(mut w) = DOWNLOADS_OPEN.write() {
+        *w = true;
+    }
+}
+
+/// Add a finished download to the history.
+pub(super) fn record_completed(thread_id: u64, state: &DownloadState, dest_dir: &Path) {
+    let entry = HistoryEntry {
+        thread_id,
+        title: state.title.clone(),
+        version: state.version.clone(),
+        hosting: state.stats.hosting.clone(),
+        size: state.stats.total,
+        duration_secs: state.stats.started.elapsed().as_secs(),
+        sha256: integrity::read_manifest(dest_dir).map(|m| m.archive_sha256),
+        finished_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0),
+    };
+    if let Ok(mut history) = HISTORY.lock() {
+        history.push(entry);
+        save_history(&history);
+    }
+}
+
+/// What a row button asked for; applied after the window is drawn
+enum RowAction {
+    Cancel(u64),
+    Retry(u64),
+    Dismiss(u64),
+    StartQueued(u64),
+    RemoveQueued(u64),
+    OpenFolder(PathBuf),
+    ForgetHistory(usize),
+    ClearHistory,
+}
+
+impl super::NoLagApp {
+    pub(super) fn draw_downloads_viewport(&mut self, ctx: &egui::Context) {
+        let is_open = DOWNLOADS_OPEN.read().map(|g| *g).unwrap_or(false);
+        if !is_open {
+            return;
+        }
+
+        let viewport_id = egui::ViewportId::from_hash_of("downloads_window");
+        let mut actions: Vec<RowAction> = Vec::new();
+
+        ctx.show_viewport_immediate(
+            viewport_id,
+            egui::ViewportBuilder::default()
+                .with_title(translate("downloads-window-title"))
+                .with_inner_size([900.0, 500.0])
+                .with_resizable(true),
+            |ctx, _class| {
+                // If user clicked the OS close (X), mark as closed and ensure viewport closes.
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    if let Ok(mut v) = DOWNLOADS_OPEN.write() {
+                        *v = false;
+                    }
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    return;
+                }
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    egui::ScrollArea::vertical()
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            self.draw_transfers(ui, &mut actions);
+                            ui.add_space(crate::ui_constants::spacing::MEDIUM);
+                            draw_queued(ui, self, &mut actions);
+                            ui.add_space(crate::ui_constants::spacing::MEDIUM);
+                            draw_history(ui, &mut actions);
+                        });
+                });
+                // Speed and ETA change without new statuses arriving
+                if !self.downloads.is_empty() {
+                    ctx.request_repaint_after(Duration::from_millis(500));
+                }
+            },
+        );
+
+        for action in actions {
+            self.apply_row_action(ctx, action);
+        }
+    }
+
+    fn draw_transfers(&self, ui: &mut egui::Ui, actions: &mut Vec<RowAction>) {
+        ui.heading(translate_with(
+            "downloads-active",
+            &[("count", self.downloads.len().to_string())],
+        ));
+        if self.downloads.is_empty() {
+            ui.label(translate("downloads-none"));
+            return;
+        }
+        let mut ids: Vec<u64> = self.downloads.keys().copied().collect();
+        ids.sort_by_key(|id| self.downloads[id].stats.started);
+        egui::Grid::new("downloads_active_grid")
+            .striped(true)
+            .num_columns(8)
+            .show(ui, |ui| {
+                for key in [
+                    "downloads-col-title",
+                    "downloads-col-phase",
+                    "downloads-col-bytes",
+                    "downloads-col-speed",
+                    "downloads-col-eta",
+                    "downloads-col-hosting",
+                    "downloads-col-limit",
+                ] {
+                    ui.strong(translate(key));
+                }
+                ui.end_row();
+
+                for id in ids {
+                    let state = &self.downloads[&id];
+                    let stats = &state.stats;
+                    ui.label(&state.title);
+                    let phase = ui.label(phase_label(state));
+                    if let Some(Progress::Error(e)) = &state.progress {
+                        phase.on_hover_text(e);
+                    }
+                    ui.label(if stats.total > 0 {
+                        format!("{} / {}", format_size(stats.done), format_size(stats.total))
+                    } else if stats.done > 0 {
+                        format_size(stats.done)
+                    } else {
+                        "—".to_string()
+                    });
+                    ui.label(
+                        stats
+                            .speed()
+                            .map(|s| format!("{}/s", format_size(s as u64)))
+                            .unwrap_or_else(|| "—".to_string()),
+                    );
+                    ui.label(
+                        stats
+                            .eta()
+                            .map(|d| format_duration(d.as_secs()))
+                            .unwrap_or_else(|| "—".to_string()),
+                    );
+                    ui.label(stats.hosting.as_deref().unwrap_or("—"));
+                    match &stats.throttle {
+                        Some(throttle) if !stats.unzipping => {
+                            let mut kib = throttle.limit_kib();
+                            let unlimited = translate("downloads-limit-none");
+                            let changed = ui
+                                .add(
+                                    egui::DragValue::new(&mut kib)
+                                        .clamp_range(0..=1_000_000)
+                                        .speed(64)
+                                        .custom_formatter(move |v, _| {
+                                            if v == 0.0 {
+                                                unlimited.clone()
+                                            } else {
+                                                format!("{v} KiB/s")
+                                            }
+                                        })
+                                        .custom_parser(|s| {
+                                            s.trim().trim_end_matches("KiB/s").trim().parse().ok()
+                                        }),
+                                )
+                                .on_hover_text(translate("downloads-limit-hint"))
+                                .changed();
+                            if changed {
+                                throttle.set_limit_kib(kib);
+                            }
+                        }
+                        _ => {
+                            ui.label("—");
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        if matches!(state.progress, Some(Progress::Error(_))) {
+                            if ui.button(translate("downloads-retry")).clicked() {
+                                actions.push(RowAction::Retry(id));
+                            }
+                            if ui.button(translate("downloads-dismiss")).clicked() {
+                                actions.push(RowAction::Dismiss(id));
+                            }
+                            return;
+                        }
+                        if let Some(prompt) = &state.captcha
+                            && ui.button(translate("downloads-captcha-retry")).clicked()
+                        {
+                            prompt.retry();
+                        }
+                        // Extraction can't be interrupted halfway
+                        let cancel = ui
+                            .add_enabled(
+                                !stats.unzipping,
+                                egui::Button::new(translate("downloads-cancel")),
+                            )
+                            .on_disabled_hover_text(translate("downloads-cancel-extracting"));
+                        if cancel.clicked() {
+                            actions.push(RowAction::Cancel(id));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn apply_row_action(&mut self, ctx: &egui::Context, action: RowAction) {
+        match action {
+            RowAction::Cancel(id) | RowAction::Dismiss(id) => {
+                // Dropping the receiver stops the transfer and removes the partial file
+                if let Some(state) = self.downloads.remove(&id) {
+                    if state.repair.is_some() {
+                        integrity::repair_failed(id, translate("downloads-cancelled"));
+                    }
+                    log::info!("Download of {} (thread {}) removed", state.title, id);
+                }
+                super::settings::remove_pending_download(id);
+                self.refresh_prefetch_library(ctx);
+            }
+            RowAction::Retry(id) => {
+                if let Some(state) = self.downloads.remove(&id) {
+                    self.start_download(ctx, id, state.title, state.version);
+                }
+            }
+            RowAction::StartQueued(id) => {
+                let (title, version) = thread_title(self, id)
+                    .unwrap_or_else(|| (format!("Thread {id}"), String::new()));
+                self.start_download(ctx, id, title, version);
+            }
+            RowAction::RemoveQueued(id) => {
+                super::settings::remove_pending_download(id);
+                self.refresh_prefetch_library(ctx);
+            }
+            RowAction::OpenFolder(folder) => super::settings::reveal_in_file_manager(&folder),
+            RowAction::ForgetHistory(idx) => {
+                if let Ok(mut history) = HISTORY.lock()
+                    && idx < history.entries.len()
+                {
+                    history.entries.remove(idx);
+                    save_history(&history);
+                }
+            }
+            RowAction::ClearHistory => {
+                if let Ok(mut history) = HISTORY.lock() {
+                    history.entries.clear();
+                    save_history(&history);
+                }
+            }
+        }
+        ctx.request_repaint();
+    }
+
+    /// Same as the card's Download button
+    fn start_download(&mut self, ctx: &egui::Context, id: u64, title: String, version: String) {
+        super::settings::record_pending_download(id);
+        let rx = crate::game_download::create_download_task(ThreadId(id).get_page(), false);
+        self.downloads
+            .insert(id, DownloadState::new(rx, title, version));
+        self.refresh_prefetch_library(ctx);
+    }
+}
+
+/// Downloads interrupted in an earlier session (pending, but not running now)
+fn draw_queued(ui: &mut egui::Ui, app: &super::NoLagApp, actions: &mut Vec<RowAction>) {
+    let queued: Vec<u64> = super::settings::with_settings(|st| {
+        st.pending_downloads
+            .iter()
+            .copied()
+            .filter(|id| !app.downloads.contains_key(id))
+            .collect()
+    });
+    ui.heading(translate_with(
+        "downloads-queued",
+        &[("count", queued.len().to_string())],
+    ));
+    if queued.is_empty() {
+        ui.label(translate("downloads-none"));
+        return;
+    }
+    egui::Grid::new("downloads_queued_grid")
+        .striped(true)
+        .num_columns(2)
+        .show(ui, |ui| {
+            for id in queued {
+                let title = thread_title(app, id)
+                    .map(|(title, _)| title)
+                    .unwrap_or_else(|| format!("Thread {id}"));
+                ui.label(title);
+                ui.horizontal(|ui| {
+                    if ui.button(translate("downloads-start")).clicked() {
+                        actions.push(RowAction::StartQueued(id));
+                    }
+                    if ui.button(translate("downloads-remove")).clicked() {
+                        actions.push(RowAction::RemoveQueued(id));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+}
+
+fn draw_history(ui: &mut egui::Ui, actions: &mut Vec<RowAction>) {
+    let Ok(history) = HISTORY.lock() else {
+        return;
+    };
+    ui.horizontal(|ui| {
+        ui.heading(translate_with(
+            "downloads-history",
+            &[("count", history.entries.len().to_string())],
+        ));
+        if !history.entries.is_empty() && ui.button(translate("downloads-clear-history")).clicked()
+        {
+            actions.push(RowAction::ClearHistory);
+        }
+    });
+    if history.entries.is_empty() {
+        ui.label(translate("downloads-none"));
+        return;
+    }
+    egui::Grid::new("downloads_history_grid")
+        .striped(true)
+        .num_columns(7)
+        .show(ui, |ui| {
+            for key in [
+                "downloads-col-title",
+                "downloads-col-size",
+                "downloads-col-hosting",
+                "downloads-col-duration",
+                "downloads-col-checksum",
+                "downloads-col-finished",
+            ] {
+                ui.strong(translate(key));
+            }
+            ui.end_row();
+
+            // Newest first
+            for (idx, entry) in history.entries.iter().enumerate().rev() {
+                ui.label(format!("{} {}", entry.title, entry.version));
+                ui.label(if entry.size > 0 {
+                    format_size(entry.size)
+                } else {
+                    "—".to_string()
+                });
+                ui.label(
+                    entry
+                        .hosting
+                        .clone()
+                        .unwrap_or_else(|| translate("downloads-local-file")),
+                );
+                ui.label(format_duration(entry.duration_secs));
+                match &entry.sha256 {
+                    Some(sha) => {
+                        let short: String = sha.chars().take(12).collect();
+                        if ui
+                            .label(egui::RichText::new(short).monospace())
+                            .on_hover_text(translate_with(
+                                "downloads-copy-checksum",
+                                &[("sha", sha.clone())],
+                            ))
+                            .clicked()
+                        {
+                            ui.output_mut(|o| o.copied_text = sha.clone());
+                        }
+                    }
+                    None => {
+                        ui.label("—");
+                    }
+                }
+                ui.label(format_finished(entry.finished_at));
+                ui.horizontal(|ui| {
+                    if let Some(folder) = super::settings::downloaded_game_folder(entry.thread_id)
+                        && ui.button(translate("downloads-open-folder")).clicked()
+                    {
+                        actions.push(RowAction::OpenFolder(folder));
+                    }
+                    if ui.button(translate("downloads-remove")).clicked() {
+                        actions.push(RowAction::ForgetHistory(idx));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+}
+
+fn phase_label(state: &DownloadState) -> String {
+    let key = if matches!(state.progress, Some(Progress::Error(_))) {
+        "downloads-phase-failed"
+    } else if state.link_choices.is_some() || state.platform_choices.is_some() {
+        "downloads-phase-choose"
+    } else if state.captcha.is_some() {
+        "downloads-phase-captcha"
+    } else if matches!(state.progress, Some(Progress::Paused)) {
+        "downloads-phase-scheduled"
+    } else if state.stats.unzipping {
+        "downloads-phase-extracting"
+    } else if state.stats.total > 0 || state.stats.done > 0 {
+        // Without a content length only the byte count grows
+        "downloads-phase-downloading"
+    } else {
+        "downloads-phase-starting"
+    };
+    translate(key)
+}
+
+/// Title and version from the loaded library or the current page
+fn thread_title(app: &super::NoLagApp, id: u64) -> Option<(String, String)> {
+    [&app.net.lib_result, &app.net.last_result]
+        .into_iter()
+        .flatten()
+        .flat_map(|msg| msg.data.iter())
+        .find(|t| t.thread_id.get() == id)
+        .map(|t| (t.title.clone(), t.version.clone()))
+}
+
+/// "12s", "3m 05s", "1h 02m"
+fn format_duration(secs: u64) -> String {
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+fn format_finished(ts: i64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(ts);
+    let days = (now - ts).max(0) / 86400;
+    if days == 0 {
+        translate("downloads-finished-today")
+    } else {
+        translate_with("downloads-finished-days-ago", &[("days", days.to_string())])
+    }
+}
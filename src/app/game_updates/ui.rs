@@ -57,7 +57,7 @@ pub fn trigger_update_all() {
     for thread_id in thread_ids {
         let tid = crate::parser::game_info::types::ThreadId(thread_id);
         let page = tid.get_page();
-        let _rx = crate::game_download::create_download_task(page);
+        let _rx = crate::game_download::create_download_task(page, false);
         crate::app::settings::record_pending_download(thread_id);
     }
 
@@ -1,5 +1,6 @@
 use eframe::egui;
 
+use super::downloads::DownloadState;
 use crate::game_download;
 use crate::views::cards::thread_card;
 
@@ -66,20 +67,38 @@ impl super::NoLagApp {
                 let cover = self.library_manager.get_cover(id);
                 let screens_slice = self.library_manager.get_screens_slice(id);
                 let progress = self.downloads.get(&id).and_then(|s| s.progress.clone());
-                let link_choices = self
+                let prompts = self
                     .downloads
                     .get(&id)
-                    .and_then(|s| s.link_choices.as_ref().map(|v| v.as_slice()));
-                thread_card(ui, t, card_w, cover, screens_slice, progress, link_choices)
+                    .map(DownloadState::prompts)
+                    .unwrap_or_default();
+                thread_card(
+                    ui,
+                    t,
+                    card_w,
+                    cover,
+                    screens_slice,
+                    progress,
+                    prompts,
+                )
             } else {
                 let cover = self.images.covers.get(&id);
                 let screens_slice = self.images.screens.get(&id).map(|v| v.as_slice());
                 let progress = self.downloads.get(&id).and_then(|s| s.progress.clone());
-                let link_choices = self
+                let prompts = self
                     .downloads
                     .get(&id)
-                    .and_then(|s| s.link_choices.as_ref().map(|v| v.as_slice()));
-                thread_card(ui, t, card_w, cover, screens_slice, progress, link_choices)
+                    .map(DownloadState::prompts)
+                    .unwrap_or_default();
+                thread_card(
+                    ui,
+                    t,
+                    card_w,
+                    cover,
+                    screens_slice,
+                    progress,
+                    prompts,
+                )
             };
 
             if hover.hovered {
@@ -152,15 +171,13 @@ impl super::NoLagApp {
                     st.rx = rx_new;
                     st.progress = Some(crate::game_download::Progress::Unknown);
                     st.link_choices = None;
+                    st.captcha = None;
+                    st.platform_choices = None;
+                    st.stats = Default::default();
                 } else {
                     self.downloads.insert(
                         id,
-                        super::downloads::DownloadState {
-                            rx: rx_new,
-                            title: t.title.clone(),
-                            progress: Some(crate::game_download::Progress::Unknown),
-                            link_choices: None,
-                        },
+                        DownloadState::new(rx_new, t.title.clone(), t.version.clone()),
                     );
                 }
                 super::settings::record_pending_download(id);
@@ -168,6 +185,81 @@ impl super::NoLagApp {
                 ctx.request_repaint();
             }
 
+            if let Some(links) = hover.selected_platform {
+                let rx = game_download::create_download_from_links(links);
+                // A repair whose platform had to be picked by hand stays a repair
+                let repair = self.downloads.get_mut(&id).and_then(|st| st.repair.take());
+                self.downloads.insert(
+                    id,
+                    DownloadState {
+                        repair,
+                        ..DownloadState::new(rx, t.title.clone(), t.version.clone())
+                    },
+                );
+                super::settings::record_pending_download(id);
+                ctx.request_repaint();
+            }
+
+            if hover.choose_platform_clicked {
+                super::settings::record_pending_download(id);
+                let rx = game_download::create_download_task(t.thread_id.get_page(), true);
+                self.downloads.insert(
+                    id,
+                    DownloadState::new(rx, t.title.clone(), t.version.clone()),
+                );
+                self.refresh_prefetch_library(ctx);
+                ctx.request_repaint();
+            }
+
+            if let Some(archive) = hover.install_file {
+                let keep = super::settings::with_settings(|st| st.keep_local_archive);
+                let rx = game_download::create_install_from_file(archive, keep);
+                self.downloads.insert(
+                    id,
+                    DownloadState::new(rx, t.title.clone(), t.version.clone()),
+                );
+                super::settings::record_pending_download(id);
+                self.refresh_prefetch_library(ctx);
+                ctx.request_repaint();
+            }
+
+            if hover.library_install_clicked
+                && !self.downloads.contains_key(&id)
+                && let Some((stored, archive)) = crate::game_download::archive_store::find(id, None)
+            {
+                let rx = game_download::create_install_from_file(archive, true);
+                self.downloads
+                    .insert(id, DownloadState::new(rx, t.title.clone(), stored.version));
+                super::settings::record_pending_download(id);
+                self.refresh_prefetch_library(ctx);
+                ctx.request_repaint();
+            }
+
+            if hover.repair_clicked
+                && !self.downloads.contains_key(&id)
+                && let Some(folder) = super::settings::downloaded_game_folder(id)
+            {
+                // Re-extract the installed archive (from the archive library when it has it,
+                // otherwise download it again); completion restores the broken files only
+                crate::game_download::integrity::mark_repairing(id);
+                let stored =
+                    crate::game_download::integrity::read_manifest(&folder).and_then(|m| {
+                        crate::game_download::archive_store::find(id, Some(&m.archive_sha256))
+                    });
+                let rx = match stored {
+                    Some((_, archive)) => game_download::create_install_from_file(archive, true),
+                    None => game_download::create_download_task(t.thread_id.get_page(), false),
+                };
+                self.downloads.insert(
+                    id,
+                    DownloadState {
+                        repair: Some(folder),
+                        ..DownloadState::new(rx, t.title.clone(), t.version.clone())
+                    },
+                );
+                ctx.request_repaint();
+            }
+
             if hover.download_clicked {
                 let should_start = match self.downloads.get(&id) {
                     None => true,
@@ -178,15 +270,10 @@ impl super::NoLagApp {
                 if should_start {
                     self.downloads.remove(&id);
                     super::settings::record_pending_download(id);
-                    let rx = game_download::create_download_task(t.thread_id.get_page());
+                    let rx = game_download::create_download_task(t.thread_id.get_page(), false);
                     self.downloads.insert(
                         id,
-                        super::downloads::DownloadState {
-                            rx,
-                            title: t.title.clone(),
-                            progress: Some(crate::game_download::Progress::Unknown),
-                            link_choices: None,
-                        },
+                        DownloadState::new(rx, t.title.clone(), t.version.clone()),
                     );
                     self.refresh_prefetch_library(ctx);
                     ctx.request_repaint();
@@ -207,15 +294,10 @@ impl super::NoLagApp {
                 if should_start {
                     self.downloads.remove(&id);
                     super::settings::record_pending_download(id);
-                    let rx = game_download::create_download_task(t.thread_id.get_page());
+                    let rx = game_download::create_download_task(t.thread_id.get_page(), false);
                     self.downloads.insert(
                         id,
-                        super::downloads::DownloadState {
-                            rx,
-                            title: t.title.clone(),
-                            progress: Some(crate::game_download::Progress::Unknown),
-                            link_choices: None,
-                        },
+                        DownloadState::new(rx, t.title.clone(), t.version.clone()),
                     );
                     self.refresh_prefetch_library(ctx);
                     ctx.request_repaint();
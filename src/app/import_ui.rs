@@ -0,0 +1,398 @@
+// Import viewport (separate OS window): register game folders extracted by hand.
+// Scans a directory, guesses title/version from folder and archive names, searches F95 for
+// each title and lets the user confirm the match (with cover previews) before the folder is
+// recorded as a downloaded game. Files are never moved.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+use eframe::egui;
+use lazy_static::lazy_static;
+
+use crate::parser::F95Thread;
+
+pub(super) mod scan;
+
+use scan::{ImportCandidate, scan_import_dir};
+
+/// Number of search results offered per folder
+const MAX_MATCHES: usize = 5;
+/// Pause between searches to stay clear of the site's rate limit
+const SEARCH_DELAY_MS: u64 = 700;
+
+#[derive(Clone)]
+enum SearchState {
+    Pending,
+    Done(Vec<F95Thread>),
+    Failed(String),
+}
+
+struct ImportRow {
+    candidate: ImportCandidate,
+    search: SearchState,
+    /// Index into the search results; None = skip this folder
+    selected: Option<usize>,
+}
+
+#[derive(Default)]
+struct ImportState {
+    dir: Option<PathBuf>,
+    rows: Vec<ImportRow>,
+    covers: HashMap<u64, Option<egui::TextureHandle>>,
+    /// Search generation; results of an older scan are dropped
+    generation: u64,
+}
+
+enum ImportMsg {
+    Search {
+        generation: u64,
+        /// Rows are keyed by folder: imported rows leave the list while searches still run
+        folder: PathBuf,
+        result: Result<Vec<F95Thread>, String>,
+    },
+    Cover {
+        thread_id: u64,
+        image: Option<(usize, usize, Vec<u8>)>,
+    },
+}
+
+lazy_static! {
+    static ref IMPORT_OPEN: RwLock<bool> = RwLock::new(false);
+    static ref STATE: Mutex<ImportState> = Mutex::new(ImportState::default());
+    static ref CHANNEL: (
+        crossbeam_channel::Sender<ImportMsg>,
+        crossbeam_channel::Receiver<ImportMsg>
+    ) = crossbeam_channel::unbounded();
+    /// Set when games were imported so the main screen refreshes the Library
+    static ref LIBRARY_CHANGED: RwLock<bool> = RwLock::new(false);
+}
+
+pub fn open_import() {
+    if let Ok(mut v) = IMPORT_OPEN.write() {
+        *v = true;
+    }
+}
+
+/// Returns true once after an import so the caller can refresh the Library snapshot.
+pub(super) fn take_library_changed() -> bool {
+    let mut changed = LIBRARY_CHANGED.write().unwrap();
+    std::mem::replace(&mut *changed, false)
+}
+
+fn start_scan(ctx: &egui::Context, dir: PathBuf) {
+    let known: Vec<PathBuf> = crate::app::settings::with_settings(|st| {
+        st.downloaded_games
+            .iter()
+            .map(|g| g.folder.clone())
+            .collect()
+    });
+    let candidates = scan_import_dir(&dir, &known);
+    log::info!(
+        "Import scan of {}: {} candidate folder(s)",
+        dir.display(),
+        candidates.len()
+    );
+
+    let mut st = STATE.lock().unwrap();
+    st.generation = st.generation.wrapping_add(1);
+    let generation = st.generation;
+    st.dir = Some(dir);
+    st.rows = candidates
+        .into_iter()
+        .map(|candidate| ImportRow {
+            candidate,
+            search: SearchState::Pending,
+            selected: None,
+        })
+        .collect();
+
+    let queries: Vec<(PathBuf, String)> = st
+        .rows
+        .iter()
+        .map(|r| (r.candidate.folder.clone(), r.candidate.parsed.title.clone()))
+        .collect();
+    let tx = CHANNEL.0.clone();
+    let ctx = ctx.clone();
+    crate::app::rt().spawn(async move {
+        for (folder, query) in queries {
+            // A newer scan replaced these rows: don't spend requests on them
+            if STATE.lock().unwrap().generation != generation {
+                log::info!("Import scan superseded, stopping its searches");
+                break;
+            }
+            let filters = crate::parser::F95Filters::default()
+                .with_category("games")
+                .with_search_query(query);
+            let result = crate::parser::fetch_list_page(1, &filters)
+                .await
+                .map(|msg| msg.data.into_iter().take(MAX_MATCHES).collect())
+                .map_err(|e| e.to_string());
+            let _ = tx.send(ImportMsg::Search {
+                generation,
+                folder,
+                result,
+            });
+            ctx.request_repaint();
+            tokio::time::sleep(std::time::Duration::from_millis(SEARCH_DELAY_MS)).await;
+        }
+    });
+}
+
+fn request_cover(ctx: &egui::Context, st: &mut ImportState, thread: &F95Thread) {
+    let id = thread.thread_id.get();
+    if st.covers.contains_key(&id) {
+        return;
+    }
+    st.covers.insert(id, None);
+    let Some(url) = crate::app::fetch::helpers::get_cover_or_first_screen_url(thread) else {
+        return;
+    };
+    let tx = CHANNEL.0.clone();
+    let ctx = ctx.clone();
+    crate::app::rt().spawn(async move {
+        let image = crate::parser::fetch_image_f95(&url).await.ok();
+        let _ = tx.send(ImportMsg::Cover {
+            thread_id: id,
+            image,
+        });
+        ctx.request_repaint();
+    });
+}
+
+fn row_mut<'a>(rows: &'a mut [ImportRow], folder: &Path) -> Option<&'a mut ImportRow> {
+    rows.iter_mut().find(|r| r.candidate.folder == folder)
+}
+
+fn poll_messages(ctx: &egui::Context, st: &mut ImportState) {
+    while let Ok(msg) = CHANNEL.1.try_recv() {
+        match msg {
+            ImportMsg::Search {
+                generation,
+                folder,
+                result,
+            } => {
+                if generation != st.generation {
+                    continue;
+                }
+                let threads = match result {
+                    Ok(threads) => threads,
+                    Err(e) => {
+                        if let Some(row) = row_mut(&mut st.rows, &folder) {
+                            row.search = SearchState::Failed(e);
+                        }
+                        continue;
+                    }
+                };
+                for t in &threads {
+                    request_cover(ctx, st, t);
+                }
+                if let Some(row) = row_mut(&mut st.rows, &folder) {
+                    // Preselect the top hit
+                    row.selected = (!threads.is_empty()).then_some(0);
+                    row.search = SearchState::Done(threads);
+                }
+            }
+            ImportMsg::Cover { thread_id, image } => {
+                let texture = image.map(|(w, h, rgba)| {
+                    ctx.load_texture(
+                        format!("import_cover_{thread_id}"),
+                        egui::ColorImage::from_rgba_unmultiplied([w, h], &rgba),
+                        egui::TextureOptions::LINEAR,
+                    )
+                });
+                st.covers.insert(thread_id, texture);
+            }
+        }
+    }
+}
+
+/// Register confirmed rows as downloaded games and cache their metadata.
+fn import_selected(st: &mut ImportState) -> usize {
+    let cache_dir = crate::app::settings::with_settings(|s| s.cache_dir.clone());
+    let mut imported = 0;
+    st.rows.retain(|row| {
+        let (SearchState::Done(threads), Some(sel)) = (&row.search, row.selected) else {
+            return true;
+        };
+        let Some(thread) = threads.get(sel) else {
+            return true;
+        };
+        let id = thread.thread_id.get();
+        let mut meta = thread.clone();
+        // Keep the installed version so update checks compare against what is on disk
+        if let Some(v) = &row.candidate.parsed.version {
+            meta.version = v.clone();
+        }
+        if let Err(e) = crate::app::fetch::helpers::save_to_cache(&cache_dir, id, &meta) {
+            log::warn!("Failed to save cache for imported thread {}: {}", id, e);
+        }
+        let exe = crate::parser::game_info::link::find_first_exe(&row.candidate.folder);
+        crate::app::settings::record_downloaded_game(id, row.candidate.folder.clone(), exe);
+        log::info!(
+            "Imported {} as thread {} ({})",
+            row.candidate.folder.display(),
+            id,
+            thread.title
+        );
+        imported += 1;
+        false
+    });
+    imported
+}
+
+pub fn draw_import_viewport(ctx: &egui::Context) {
+    let is_open = IMPORT_OPEN.read().map(|g| *g).unwrap_or(false);
+    if !is_open {
+        return;
+    }
+
+    let viewport_id = egui::ViewportId::from_hash_of("import_window");
+    ctx.show_viewport_immediate(
+        viewport_id,
+        egui::ViewportBuilder::default()
+            .with_title(crate::localization::translate("import-window-title"))
+            .with_inner_size([760.0, 560.0])
+            .with_resizable(true),
+        move |ctx, _class| {
+            if ctx.input(|i| i.viewport().close_requested()) {
+                if let Ok(mut v) = IMPORT_OPEN.write() {
+                    *v = false;
+                }
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                ctx.request_repaint();
+                return;
+            }
+
+            let mut st = STATE.lock().unwrap();
+            poll_messages(ctx, &mut st);
+
+            let mut picked: Option<PathBuf> = None;
+            egui::TopBottomPanel::top("import_toolbar").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(crate::localization::translate("import-choose-folder"))
+                        .clicked()
+                    {
+                        let init = st.dir.clone().unwrap_or_else(|| {
+                            std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+                        });
+                        picked = rfd::FileDialog::new().set_directory(init).pick_folder();
+                    }
+                    if let Some(dir) = &st.dir {
+                        ui.label(dir.to_string_lossy());
+                    }
+                });
+            });
+            if let Some(dir) = picked {
+                drop(st);
+                start_scan(ctx, dir);
+                st = STATE.lock().unwrap();
+            }
+
+            egui::TopBottomPanel::bottom("import_actions").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let selected = st.rows.iter().filter(|r| r.selected.is_some()).count();
+                    if ui
+                        .add_enabled(
+                            selected > 0,
+                            egui::Button::new(crate::localization::translate_with(
+                                "import-confirm",
+                                &[("count", selected.to_string())],
+                            )),
+                        )
+                        .clicked()
+                    {
+                        let imported = import_selected(&mut st);
+                        if imported > 0 {
+                            *LIBRARY_CHANGED.write().unwrap() = true;
+                        }
+                    }
+                });
+            });
+
+            egui::CentralPanel::default().show(ctx, |ui| {
+                if st.dir.is_some() && st.rows.is_empty() {
+                    ui.label(crate::localization::translate("import-nothing-found"));
+                    return;
+                }
+                let st = &mut *st;
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        for row in st.rows.iter_mut() {
+                            draw_import_row(ui, row, &st.covers);
+                            ui.separator();
+                        }
+                    });
+            });
+        },
+    );
+}
+
+fn draw_import_row(
+    ui: &mut egui::Ui,
+    row: &mut ImportRow,
+    covers: &HashMap<u64, Option<egui::TextureHandle>>,
+) {
+    let folder_name = row
+        .candidate
+        .folder
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    ui.horizontal(|ui| {
+        ui.strong(folder_name);
+        let parsed = &row.candidate.parsed;
+        let guess = match &parsed.version {
+            Some(v) => format!("→ {} ({})", parsed.title, v),
+            None => format!("→ {}", parsed.title),
+        };
+        ui.label(egui::RichText::new(guess).weak());
+    });
+
+    match &row.search {
+        SearchState::Pending => {
+            ui.horizontal(|ui| {
+                ui.add(egui::Spinner::new());
+                ui.label(crate::localization::translate("import-searching"));
+            });
+        }
+        SearchState::Failed(e) => {
+            ui.colored_label(
+                egui::Color32::RED,
+                crate::localization::translate_with("error-prefix", &[("err", e.clone())]),
+            );
+        }
+        SearchState::Done(threads) if threads.is_empty() => {
+            ui.label(crate::localization::translate("import-no-matches"));
+        }
+        SearchState::Done(threads) => {
+            ui.horizontal_wrapped(|ui| {
+                for (i, t) in threads.iter().enumerate() {
+                    ui.vertical(|ui| {
+                        ui.set_width(120.0);
+                        let size = egui::vec2(120.0, 68.0);
+                        match covers.get(&t.thread_id.get()).and_then(|c| c.as_ref()) {
+                            Some(tex) => {
+                                ui.add(egui::Image::new(tex).fit_to_exact_size(size));
+                            }
+                            None => {
+                                ui.allocate_exact_size(size, egui::Sense::hover());
+                            }
+                        }
+                        ui.radio_value(&mut row.selected, Some(i), &t.title)
+                            .on_hover_text(format!("{} — {}", t.title, t.version));
+                    });
+                }
+                ui.vertical(|ui| {
+                    ui.radio_value(
+                        &mut row.selected,
+                        None,
+                        crate::localization::translate("import-skip"),
+                    );
+                });
+            });
+        }
+    }
+}
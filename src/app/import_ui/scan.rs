@@ -0,0 +1,180 @@
+// Folder import scanning: list game folders in a directory and guess title/version from names.
+// Archive names next to the folders (e.g. "Game-0.5-pc.zip" beside "Game") fill in missing versions.
+
+use std::path::{Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+const ARCHIVE_EXTS: [&str; 3] = ["zip", "7z", "rar"];
+
+lazy_static! {
+    // Platform/build suffixes that uploaders append to folder and archive names
+    static ref PLATFORM_SUFFIX: Regex = Regex::new(
+        r"(?i)[\s_\-\.]+(pc|win|win64|win32|windows|linux|mac|osx|market|steam|x64|x86)$"
+    )
+    .unwrap();
+    // Trailing version: "v0.5.2", "0.12a", "Ep.3", "1.0_fix" etc.
+    static ref VERSION: Regex = Regex::new(
+        r"(?i)[\s_\-\.]+(?:v\.?\s?)?(\d+(?:[._]\d+)*[a-z]?(?:[\s_\-]?(?:fix|final|beta|alpha|public))?)$"
+    )
+    .unwrap();
+    static ref BRACKETS: Regex = Regex::new(r"[\[\(]([^\]\)]*)[\]\)]").unwrap();
+    static ref CAMEL: Regex = Regex::new(r"([a-z])([A-Z])").unwrap();
+    static ref SPACES: Regex = Regex::new(r"\s+").unwrap();
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedName {
+    pub title: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportCandidate {
+    pub folder: PathBuf,
+    pub parsed: ParsedName,
+}
+
+/// Guess game title and version from a folder or archive file name.
+pub fn parse_game_name(name: &str) -> ParsedName {
+    let mut s = name.trim().to_string();
+    // Drop archive extension
+    if let Some((stem, _)) = s
+        .rsplit_once('.')
+        .filter(|(_, ext)| ARCHIVE_EXTS.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+    {
+        s = stem.to_string();
+    }
+
+    // Bracketed groups: keep a version-looking one, drop the rest ([Dev], (Final) ...)
+    let mut version: Option<String> = None;
+    for cap in BRACKETS.captures_iter(&s.clone()) {
+        let inner = cap[1].trim();
+        if version.is_none() {
+            version = VERSION
+                .captures(&format!(" {inner}"))
+                .map(|v| v[1].to_string());
+        }
+    }
+    s = BRACKETS.replace_all(&s, " ").trim().to_string();
+
+    loop {
+        let stripped = PLATFORM_SUFFIX.replace(&s, "").trim().to_string();
+        if stripped == s {
+            break;
+        }
+        s = stripped;
+    }
+    if let Some(cap) = VERSION.captures(&s) {
+        let title_part = s[..cap.get(0).unwrap().start()].trim();
+        // Titles that are only a number ("1979") are not versions
+        if !title_part.is_empty() {
+            version.get_or_insert_with(|| cap[1].replace('_', "."));
+            s = title_part.to_string();
+        }
+        s = PLATFORM_SUFFIX.replace(&s, "").trim().to_string();
+    }
+
+    let title = CAMEL.replace_all(&s, "$1 $2").replace(['_', '-', '.'], " ");
+    let title = SPACES.replace_all(title.trim(), " ").to_string();
+    ParsedName { title, version }
+}
+
+fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| ARCHIVE_EXTS.iter().any(|a| e.eq_ignore_ascii_case(a)))
+}
+
+/// List game folders directly inside `dir`, skipping folders already in the library.
+pub fn scan_import_dir(dir: &Path, known_folders: &[PathBuf]) -> Vec<ImportCandidate> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let known: Vec<PathBuf> = known_folders
+        .iter()
+        .map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.clone()))
+        .collect();
+
+    let mut folders = Vec::new();
+    let mut archives = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            let canon = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            if !known.contains(&canon) {
+                folders.push((path.clone(), parse_game_name(name)));
+            }
+        } else if is_archive(&path) {
+            archives.push(parse_game_name(name));
+        }
+    }
+    folders.sort_by(|a, b| a.0.cmp(&b.0));
+
+    folders
+        .into_iter()
+        .map(|(folder, mut parsed)| {
+            if parsed.version.is_none() {
+                parsed.version = archives
+                    .iter()
+                    .find(|a| a.title.eq_ignore_ascii_case(&parsed.title))
+                    .and_then(|a| a.version.clone());
+            }
+            ImportCandidate { folder, parsed }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(title: &str, version: Option<&str>) -> ParsedName {
+        ParsedName {
+            title: title.to_string(),
+            version: version.map(|v| v.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_game_name_variants() {
+        assert_eq!(
+            parse_game_name("SummertimeSaga-0.20.16-pc"),
+            parsed("Summertime Saga", Some("0.20.16"))
+        );
+        assert_eq!(
+            parse_game_name("Some_Game_v0.12a.zip"),
+            parsed("Some Game", Some("0.12a"))
+        );
+        assert_eq!(
+            parse_game_name("My Game [v1.0] [Dev Name]"),
+            parsed("My Game", Some("1.0"))
+        );
+        assert_eq!(
+            parse_game_name("Game.Name.v0.3.1-win.7z"),
+            parsed("Game Name", Some("0.3.1"))
+        );
+        assert_eq!(parse_game_name("Plain Title"), parsed("Plain Title", None));
+        assert_eq!(parse_game_name("1979"), parsed("1979", None));
+    }
+
+    #[test]
+    fn test_scan_import_dir_uses_archive_version() {
+        let dir = std::env::temp_dir().join(format!("f95-import-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("CoolGame")).unwrap();
+        std::fs::create_dir_all(dir.join("Known Game")).unwrap();
+        std::fs::write(dir.join("CoolGame-1.2-pc.zip"), b"zip").unwrap();
+
+        let found = scan_import_dir(&dir, &[dir.join("Known Game")]);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].parsed, parsed("Cool Game", Some("1.2")));
+    }
+}
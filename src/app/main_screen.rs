@@ -3,9 +3,9 @@ use std::time::{Duration, Instant};
 
 use eframe::egui;
 
-use super::{NoLagApp, about_ui, errors_ui, logs_ui, settings, update_ui};
+use super::{NoLagApp, about_ui, downloads_ui, errors_ui, import_ui, logs_ui, settings, update_ui};
 use crate::parser::F95Thread;
-use crate::types::TagLogic;
+use crate::types::{LibrarySorting, TagLogic};
 use crate::views::filters::draw_filters_panel;
 
 /// Grid layout parameters for the card display
@@ -190,6 +190,40 @@ fn apply_library_filters(app: &NoLagApp, display_data: &mut Vec<F95Thread>) {
     );
 }
 
+/// Order Library cards client-side by the selected library sorting
+fn apply_library_sort(app: &NoLagApp, display_data: &mut [F95Thread]) {
+    if !app.filters.library_only {
+        return;
+    }
+
+    match app.filters.library_sort {
+        LibrarySorting::Added => {}
+        LibrarySorting::Title => {
+            display_data.sort_by_key(|t| t.title.to_lowercase());
+        }
+        LibrarySorting::Playtime | LibrarySorting::LastPlayed => {
+            let stats = settings::with_settings(|st| {
+                st.downloaded_games
+                    .iter()
+                    .map(|g| (g.thread_id, (g.total_playtime_secs, g.last_played)))
+                    .collect::<std::collections::HashMap<u64, (u64, Option<i64>)>>()
+            });
+            let key = |t: &F95Thread| {
+                let (playtime, last_played) = stats
+                    .get(&t.thread_id.get())
+                    .copied()
+                    .unwrap_or((0, None));
+                match app.filters.library_sort {
+                    LibrarySorting::Playtime => playtime as i64,
+                    _ => last_played.unwrap_or(i64::MIN),
+                }
+            };
+            // Most played / most recent first
+            display_data.sort_by_key(|t| std::cmp::Reverse(key(t)));
+        }
+    }
+}
+
 /// Render bottom controls: library summary or pagination
 fn render_bottom_controls(
     ui: &mut egui::Ui,
@@ -275,6 +309,7 @@ fn render_central_content(ui: &mut egui::Ui, app: &mut NoLagApp, ctx: &egui::Con
 
         // Apply client-side filters in Library mode
         apply_library_filters(app, &mut display_data);
+        apply_library_sort(app, &mut display_data);
 
         // Draw the threads grid
         app.draw_threads_grid(
@@ -322,15 +357,17 @@ struct FiltersPanelResult {
     open_settings: bool,
     open_logs: bool,
     open_about: bool,
+    open_downloads: bool,
     prev_query: String,
 }
 
 /// Draw filters panel and return interaction results
 fn draw_filters(app: &mut NoLagApp, ctx: &egui::Context) -> FiltersPanelResult {
     let prev_query = app.filters.query.clone();
-    let (apply, open_settings, open_logs, open_about) = draw_filters_panel(
+    let (apply, open_settings, open_logs, open_about, open_downloads) = draw_filters_panel(
         ctx,
         &mut app.filters.sort,
+        &mut app.filters.library_sort,
         &mut app.filters.date_limit,
         &mut app.filters.include_logic,
         &mut app.filters.include_tags,
@@ -349,6 +386,7 @@ fn draw_filters(app: &mut NoLagApp, ctx: &egui::Context) -> FiltersPanelResult {
         open_settings,
         open_logs,
         open_about,
+        open_downloads,
         prev_query,
     }
 }
@@ -389,7 +427,7 @@ fn handle_query_debounce(
     }
 }
 
-/// Handle panel button clicks (settings, logs, about)
+/// Handle panel button clicks (settings, logs, about, downloads)
 fn handle_panel_buttons(app: &mut NoLagApp, ctx: &egui::Context, result: &FiltersPanelResult) {
     if result.open_settings {
         settings::open_settings(&mut app.settings_ui);
@@ -403,6 +441,10 @@ fn handle_panel_buttons(app: &mut NoLagApp, ctx: &egui::Context, result: &Filter
         about_ui::open_about();
         ctx.request_repaint();
     }
+    if result.open_downloads {
+        downloads_ui::open_downloads();
+        ctx.request_repaint();
+    }
 }
 
 /// Auto-save selected tags if enabled in settings
@@ -494,6 +536,7 @@ fn draw_overlays_and_viewports(ctx: &egui::Context, settings_ui: &mut settings::
     errors_ui::draw_errors_button(ctx, bottom_offset);
     errors_ui::draw_errors_viewport(ctx);
     logs_ui::draw_logs_viewport(ctx);
+    import_ui::draw_import_viewport(ctx);
     about_ui::draw_about_viewport(ctx);
     settings::draw_settings_viewport(ctx, settings_ui);
     crate::views::bookmarks_management::draw_bookmarks_management_viewport(ctx);
@@ -545,4 +588,10 @@ pub(super) fn update_main(app: &mut NoLagApp, ctx: &egui::Context) {
 
     // 11. Draw overlays and viewports
     draw_overlays_and_viewports(ctx, &mut app.settings_ui);
+    app.draw_downloads_viewport(ctx);
+
+    // 12. Pick up games imported from existing folders
+    if import_ui::take_library_changed() {
+        app.refresh_prefetch_library(ctx);
+    }
 }
@@ -16,8 +16,8 @@ pub use store::{
 
 // Helpers: filesystem utilities, launching games, and convenience funcs
 pub use helpers::{
-    copy_dir_all, game_folder_exists, move_directory, open_in_browser, reveal_in_file_manager,
-    run_downloaded_game,
+    copy_dir_all, game_folder_exists, is_game_running, move_directory, open_in_browser,
+    reveal_in_file_manager, run_downloaded_game, running_game, stop_game,
 };
 
 // UI: egui viewport window for settings and separate eframe App
@@ -4,15 +4,27 @@
 // - open: cross-platform helpers to open browser and reveal folders
 // - paths: path utils and folder existence checks
 // - fs_ops: move/copy directory helpers
+// - html_server: localhost HTTP server for HTML / RPG Maker MV-MZ web games
+// - launch: launch profiles -> Command (shell-style splitting, placeholders)
+// - sandbox: Linux bubblewrap/firejail wrapping for sandboxed launches
 // - run: game launching logic (Windows-specific runner + cross-platform fallback)
+// - session: play session tracking (wait for the game to exit, accumulate playtime)
+// - running: registry of launched games (running indicator, double-launch guard, stop)
 
 pub mod fs_ops;
+pub mod html_server;
+pub mod launch;
 pub mod open;
 pub mod paths;
 pub mod run;
+pub mod running;
+#[cfg(target_os = "linux")]
+pub mod sandbox;
+pub mod session;
 
 // Re-export public API to preserve existing imports via crate::app::settings::helpers::*
 pub use fs_ops::{copy_dir_all, move_directory};
 pub use open::{open_in_browser, reveal_in_file_manager};
 pub use paths::game_folder_exists;
 pub use run::run_downloaded_game;
+pub use running::{is_game_running, running_game, stop_game};
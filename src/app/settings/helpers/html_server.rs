@@ -0,0 +1,338 @@
+// Built-in localhost HTTP server for HTML games.
+// HTML games and RPG Maker MV/MZ `www/index.html` builds often break under file:// because
+// browsers block audio/JSON loads (CORS). We serve the game folder from an ephemeral
+// 127.0.0.1 port on the app's tokio runtime and open the page in the default browser.
+// The server lives until the game is stopped from the card; the session is recorded then.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use super::open::open_in_browser;
+use super::running;
+use crate::app::settings::store::record_play_session;
+
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+lazy_static! {
+    static ref SERVERS: Mutex<HashMap<u64, oneshot::Sender<()>>> = Mutex::new(HashMap::new());
+}
+
+/// Locate the page to serve: `www/index.html` (RPG Maker MV/MZ) or `index.html`,
+/// at the game root or one folder below it.
+pub fn find_html_entry(folder: &Path) -> Option<PathBuf> {
+    let candidates = |dir: &Path| [dir.join("www").join("index.html"), dir.join("index.html")];
+    if let Some(p) = candidates(folder).into_iter().find(|p| p.is_file()) {
+        return Some(p);
+    }
+    let mut subdirs: Vec<PathBuf> = std::fs::read_dir(folder)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    subdirs.sort();
+    subdirs
+        .iter()
+        .flat_map(|d| candidates(d))
+        .find(|p| p.is_file())
+}
+
+/// Serve the directory of `index` on an ephemeral localhost port and open it in the browser.
+/// The game is registered as running until `stop_server` is called.
+pub fn serve_html_game(thread_id: u64, index: PathBuf) {
+    let Some(root) = index.parent().map(|p| p.to_path_buf()) else {
+        return;
+    };
+    let page = index
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "index.html".to_string());
+    let (tx, rx) = oneshot::channel::<()>();
+    SERVERS.lock().unwrap().insert(thread_id, tx);
+    running::register(thread_id, index.clone(), None);
+
+    crate::app::rt().spawn(async move {
+        let listener = match TcpListener::bind(("127.0.0.1", 0)).await {
+            Ok(l) => l,
+            Err(e) => {
+                log::error!("HTML server for thread {thread_id}: bind failed: {e}");
+                SERVERS.lock().unwrap().remove(&thread_id);
+                running::unregister(thread_id);
+                return;
+            }
+        };
+        let port = listener.local_addr().map(|a| a.port()).unwrap_or(0);
+        let url = format!("http://127.0.0.1:{port}/{}", percent_encode(&page));
+        log::info!(
+            "Serving HTML game for thread {thread_id} from {} at {url}",
+            root.display()
+        );
+        open_in_browser(&url);
+
+        let started = Instant::now();
+        let mut rx = rx;
+        loop {
+            tokio::select! {
+                _ = &mut rx => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let root = root.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_connection(stream, &root).await {
+                                log::debug!("HTML server connection error: {e}");
+                            }
+                        });
+                    }
+                    Err(e) => log::warn!("HTML server accept failed: {e}"),
+                },
+            }
+        }
+
+        log::info!("HTML server for thread {thread_id} stopped");
+        running::unregister(thread_id);
+        let ended_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        record_play_session(thread_id, ended_at, started.elapsed().as_secs());
+    });
+}
+
+/// Shut down the server of a game. Returns false when the game is not served by us.
+pub fn stop_server(thread_id: u64) -> bool {
+    match SERVERS.lock().unwrap().remove(&thread_id) {
+        Some(tx) => {
+            let _ = tx.send(());
+            true
+        }
+        None => false,
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, root: &Path) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 2048];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || head.len() + n > MAX_REQUEST_HEAD {
+            return Ok(());
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let mut request = lines.next().unwrap_or("").split_whitespace();
+    let method = request.next().unwrap_or("");
+    let target = request.next().unwrap_or("/");
+    let range = lines
+        .filter_map(|l| l.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case("range"))
+        .map(|(_, v)| v.trim().to_string());
+
+    if method != "GET" && method != "HEAD" {
+        return write_status(&mut stream, "405 Method Not Allowed").await;
+    }
+    let Some(path) = resolve_request_path(root, target) else {
+        return write_status(&mut stream, "403 Forbidden").await;
+    };
+    let path = if path.is_dir() {
+        path.join("index.html")
+    } else {
+        path
+    };
+    let Ok(mut file) = tokio::fs::File::open(&path).await else {
+        return write_status(&mut stream, "404 Not Found").await;
+    };
+    let len = file.metadata().await?.len();
+
+    // Malformed or non-bytes ranges are ignored and the whole file is served (RFC 9110 14.2)
+    let (status, start, end) = match range.as_deref().and_then(|r| parse_range(r, len)) {
+        Some(ByteRange::Satisfiable(s, e)) => ("206 Partial Content", s, e),
+        Some(ByteRange::Unsatisfiable) => {
+            let resp = format!(
+                "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{len}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            );
+            return stream.write_all(resp.as_bytes()).await;
+        }
+        None => ("200 OK", 0, len.saturating_sub(1)),
+    };
+    let body_len = if len == 0 { 0 } else { end - start + 1 };
+    let mut resp = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {}\r\nContent-Length: {body_len}\r\nAccept-Ranges: bytes\r\nCache-Control: no-cache\r\nConnection: close\r\n",
+        content_type(&path)
+    );
+    if status.starts_with("206") {
+        resp.push_str(&format!("Content-Range: bytes {start}-{end}/{len}\r\n"));
+    }
+    resp.push_str("\r\n");
+    stream.write_all(resp.as_bytes()).await?;
+    if method == "GET" && body_len > 0 {
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+        tokio::io::copy(&mut file.take(body_len), &mut stream).await?;
+    }
+    stream.flush().await
+}
+
+async fn write_status(stream: &mut TcpStream, status: &str) -> std::io::Result<()> {
+    let resp = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    stream.write_all(resp.as_bytes()).await
+}
+
+/// Map a request target to a file under `root`; None if it tries to escape the root.
+fn resolve_request_path(root: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next().unwrap_or("");
+    let decoded = percent_decode(path)?;
+    let mut out = root.to_path_buf();
+    for comp in Path::new(decoded.trim_start_matches('/')).components() {
+        match comp {
+            Component::Normal(c) => out.push(c),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    /// Inclusive offsets inside the file
+    Satisfiable(u64, u64),
+    /// Well-formed, but nothing of the file is in it
+    Unsatisfiable,
+}
+
+/// Parse the first range of a `bytes=start-end` header; None if it isn't a valid bytes range.
+fn parse_range(value: &str, len: u64) -> Option<ByteRange> {
+    let (unit, specs) = value.split_once('=')?;
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+    let (s, e) = specs.split(',').next()?.trim().split_once('-')?;
+    let (start, end) = match (s.trim(), e.trim()) {
+        ("", suffix) => {
+            let n: u64 = suffix.parse().ok()?;
+            if n == 0 || len == 0 {
+                return Some(ByteRange::Unsatisfiable);
+            }
+            (len.saturating_sub(n), len - 1)
+        }
+        (s, "") => (s.parse().ok()?, len.saturating_sub(1)),
+        (s, e) => {
+            let (start, end): (u64, u64) = (s.parse().ok()?, e.parse().ok()?);
+            if end < start {
+                return None;
+            }
+            (start, end.min(len.saturating_sub(1)))
+        }
+    };
+    Some(if start < len {
+        ByteRange::Satisfiable(start, end)
+    } else {
+        ByteRange::Unsatisfiable
+    })
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "xml" => "application/xml",
+        "wasm" => "application/wasm",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "ogg" => "audio/ogg",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_request_path_stays_in_root() {
+        let root = Path::new("/games/g/www");
+        assert_eq!(
+            resolve_request_path(root, "/audio/bgm/Theme%201.ogg?v=2"),
+            Some(root.join("audio").join("bgm").join("Theme 1.ogg"))
+        );
+        assert_eq!(resolve_request_path(root, "/"), Some(root.to_path_buf()));
+        assert_eq!(resolve_request_path(root, "/../secret.txt"), None);
+        assert_eq!(resolve_request_path(root, "/img/%2e%2e/%2e%2e/x"), None);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        use ByteRange::{Satisfiable, Unsatisfiable};
+        assert_eq!(parse_range("bytes=0-99", 1000), Some(Satisfiable(0, 99)));
+        assert_eq!(parse_range("bytes=500-", 1000), Some(Satisfiable(500, 999)));
+        assert_eq!(parse_range("bytes=-100", 1000), Some(Satisfiable(900, 999)));
+        assert_eq!(
+            parse_range("bytes=900-5000", 1000),
+            Some(Satisfiable(900, 999))
+        );
+        assert_eq!(parse_range("bytes=1000-", 1000), Some(Unsatisfiable));
+        assert_eq!(parse_range("bytes=-0", 1000), Some(Unsatisfiable));
+        // Not a valid bytes range: ignored, the whole file is served
+        assert_eq!(parse_range("items=0-1", 1000), None);
+        assert_eq!(parse_range("bytes=abc", 1000), None);
+        assert_eq!(parse_range("bytes=99-0", 1000), None);
+        assert_eq!(parse_range("bytes=-", 1000), None);
+    }
+}
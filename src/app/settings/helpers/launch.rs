@@ -0,0 +1,245 @@
+// Launch command building: shell-style command line splitting, placeholder expansion
+// and turning a per-game launch profile (or the global custom launch template) into a Command.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::app::settings::store::LaunchProfile;
+
+/// Values substituted for {{path}}, {{dir}}, {{title}} and {{thread_id}}.
+pub struct LaunchVars<'a> {
+    /// Game executable
+    pub path: &'a Path,
+    /// Directory containing the executable
+    pub dir: &'a Path,
+    pub title: &'a str,
+    pub thread_id: u64,
+}
+
+impl LaunchVars<'_> {
+    pub fn expand(&self, s: &str) -> String {
+        s.replace("{{path}}", &self.path.to_string_lossy())
+            .replace("{{dir}}", &self.dir.to_string_lossy())
+            .replace("{{title}}", self.title)
+            .replace("{{thread_id}}", &self.thread_id.to_string())
+    }
+}
+
+/// Split a command line into words using shell-style quoting:
+/// - whitespace separates words outside of quotes
+/// - '...' is taken literally
+/// - inside "...", a backslash escapes only `"` and `\`
+/// - outside quotes, a backslash escapes whitespace, quotes and `\`; other backslashes are
+///   kept as-is so unquoted Windows paths like C:\Games\game.exe still work
+pub fn split_cmdline(s: &str) -> Result<Vec<String>, String> {
+    #[derive(PartialEq)]
+    enum Quote {
+        None,
+        Single,
+        Double,
+    }
+
+    let mut out = Vec::new();
+    let mut cur = String::new();
+    // Distinguishes an empty quoted word ("") from no word at all
+    let mut in_word = false;
+    let mut quote = Quote::None;
+    let mut chars = s.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match quote {
+            Quote::Single => {
+                if ch == '\'' {
+                    quote = Quote::None;
+                } else {
+                    cur.push(ch);
+                }
+            }
+            Quote::Double => match ch {
+                '"' => quote = Quote::None,
+                '\\' if matches!(chars.peek(), Some('"') | Some('\\')) => {
+                    cur.push(chars.next().unwrap());
+                }
+                _ => cur.push(ch),
+            },
+            Quote::None => match ch {
+                '\'' => {
+                    quote = Quote::Single;
+                    in_word = true;
+                }
+                '"' => {
+                    quote = Quote::Double;
+                    in_word = true;
+                }
+                '\\' if chars
+                    .peek()
+                    .is_some_and(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\')) =>
+                {
+                    cur.push(chars.next().unwrap());
+                    in_word = true;
+                }
+                c if c.is_whitespace() => {
+                    if in_word {
+                        out.push(std::mem::take(&mut cur));
+                        in_word = false;
+                    }
+                }
+                c => {
+                    cur.push(c);
+                    in_word = true;
+                }
+            },
+        }
+    }
+
+    if quote != Quote::None {
+        return Err(format!("unterminated quote in command line: {s}"));
+    }
+    if in_word {
+        out.push(cur);
+    }
+    Ok(out)
+}
+
+/// Resolve the executable a profile should start: its override (relative to the game folder)
+/// or the detected one.
+pub fn profile_exe(profile: Option<&LaunchProfile>, folder: &Path, detected: &Path) -> PathBuf {
+    match profile.and_then(|p| p.exe_override.as_ref()) {
+        Some(p) if p.is_absolute() => p.clone(),
+        Some(p) => folder.join(p),
+        None => detected.to_path_buf(),
+    }
+}
+
+/// Build the command for a profile. The wrapper (profile wrapper, or the global custom launch
+/// template when the profile has none) is split first and placeholders are expanded per word,
+/// so paths with spaces never need extra quoting. Without {{path}} the executable is appended.
+pub fn build_command(
+    profile: Option<&LaunchProfile>,
+    global_wrapper: &str,
+    folder: &Path,
+    exe: &Path,
+    vars: &LaunchVars,
+) -> Result<Command, String> {
+    let wrapper = profile
+        .map(|p| p.wrapper.as_str())
+        .filter(|w| !w.trim().is_empty())
+        .unwrap_or(global_wrapper);
+
+    let mut words: Vec<String> = Vec::new();
+    if !wrapper.trim().is_empty() {
+        let wrapper_words = split_cmdline(wrapper)?;
+        let has_path = wrapper_words.iter().any(|w| w.contains("{{path}}"));
+        words.extend(wrapper_words.iter().map(|w| vars.expand(w)));
+        if !has_path {
+            words.push(exe.to_string_lossy().to_string());
+        }
+    } else {
+        words.push(exe.to_string_lossy().to_string());
+    }
+    if let Some(p) = profile {
+        words.extend(split_cmdline(&p.args)?.iter().map(|w| vars.expand(w)));
+    }
+
+    let (prog, args) = words
+        .split_first()
+        .ok_or_else(|| "empty command after parsing".to_string())?;
+    let mut cmd = Command::new(prog);
+    cmd.args(args);
+
+    let cwd = match profile.and_then(|p| p.working_dir.as_ref()) {
+        Some(d) => {
+            let d = PathBuf::from(vars.expand(&d.to_string_lossy()));
+            if d.is_absolute() { d } else { folder.join(d) }
+        }
+        None => vars.dir.to_path_buf(),
+    };
+    cmd.current_dir(cwd);
+
+    if let Some(p) = profile {
+        for (k, v) in &p.env {
+            if !k.trim().is_empty() {
+                cmd.env(k.trim(), vars.expand(v));
+            }
+        }
+    }
+    Ok(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_cmdline_quoting() {
+        assert_eq!(
+            split_cmdline(r#"wine "C:\Games\My Game\game.exe" --flag"#).unwrap(),
+            vec!["wine", r"C:\Games\My Game\game.exe", "--flag"]
+        );
+        assert_eq!(
+            split_cmdline(r#"echo "say \"hi\"" 'it''s' a\ b "" x"#).unwrap(),
+            vec!["echo", r#"say "hi""#, "its", "a b", "", "x"]
+        );
+        assert_eq!(
+            split_cmdline(r"C:\Start.exe /box:Test").unwrap(),
+            vec![r"C:\Start.exe", "/box:Test"]
+        );
+        assert!(split_cmdline(r#"broken "quote"#).is_err());
+        assert!(split_cmdline("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_build_command_expands_placeholders_per_word() {
+        let exe = PathBuf::from("/games/My Game/game.exe");
+        let dir = PathBuf::from("/games/My Game");
+        let vars = LaunchVars {
+            path: &exe,
+            dir: &dir,
+            title: "My Game",
+            thread_id: 42,
+        };
+        let profile = LaunchProfile {
+            name: "with mod".to_string(),
+            args: "--title {{title}} --id={{thread_id}}".to_string(),
+            wrapper: "wine {{path}}".to_string(),
+            env: vec![("SAVE_DIR".to_string(), "{{dir}}/saves".to_string())],
+            ..Default::default()
+        };
+        let cmd = build_command(Some(&profile), "", &dir, &exe, &vars).unwrap();
+        let args: Vec<_> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(cmd.get_program(), "wine");
+        assert_eq!(
+            args,
+            vec!["/games/My Game/game.exe", "--title", "My Game", "--id=42"]
+        );
+        assert_eq!(cmd.get_current_dir(), Some(dir.as_path()));
+        let env: Vec<_> = cmd.get_envs().collect();
+        assert_eq!(env.len(), 1);
+        assert_eq!(
+            env[0].1.map(|v| v.to_string_lossy().to_string()),
+            Some("/games/My Game/saves".to_string())
+        );
+    }
+
+    #[test]
+    fn test_build_command_global_wrapper_without_path_appends_exe() {
+        let exe = PathBuf::from("/games/g/game.exe");
+        let dir = PathBuf::from("/games/g");
+        let vars = LaunchVars {
+            path: &exe,
+            dir: &dir,
+            title: "G",
+            thread_id: 1,
+        };
+        let cmd = build_command(None, "sandbox --box x", &dir, &exe, &vars).unwrap();
+        let args: Vec<_> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(cmd.get_program(), "sandbox");
+        assert_eq!(args, vec!["--box", "x", "/games/g/game.exe"]);
+    }
+}
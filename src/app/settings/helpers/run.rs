@@ -1,16 +1,91 @@
 // Game launching logic: choose best executable and start the game.
-// Windows has specific spawning strategies; non-Windows reveals the folder.
+// Windows has specific spawning strategies; non-Windows reveals the folder unless a
+// launch profile, custom launch template or the Linux sandbox says how to start the game.
+// Spawned processes are handed to the session tracker to accumulate playtime.
 
 use std::path::{Path, PathBuf};
+use std::process::Child;
 
+use super::html_server::{find_html_entry, serve_html_game};
+use super::launch::{LaunchVars, build_command, profile_exe};
 use super::open::reveal_in_file_manager;
+use super::session::track_session;
 use crate::app::settings::store::{
-    APP_SETTINGS, downloaded_game_exe, downloaded_game_folder, record_downloaded_game,
-    save_settings_to_disk,
+    APP_SETTINGS, LaunchProfile, active_launch_profile, downloaded_game_exe,
+    downloaded_game_folder, record_downloaded_game, save_settings_to_disk,
 };
 
+/// Start a game through its launch profile and/or the global custom launch template.
+/// `sandbox` is Some(allow_network) when the game must run inside the Linux sandbox.
+fn run_custom(
+    profile: Option<&LaunchProfile>,
+    folder: &Path,
+    exe: &Path,
+    title: &str,
+    thread_id: u64,
+    sandbox: Option<bool>,
+) -> Option<Child> {
+    // Make path absolute if possible to avoid any resolution differences
+    let abs_exe = std::fs::canonicalize(exe).unwrap_or_else(|_| exe.to_path_buf());
+    let dir = abs_exe
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(|| folder.to_path_buf());
+    let vars = LaunchVars {
+        path: &abs_exe,
+        dir: &dir,
+        title,
+        thread_id,
+    };
+    let template = APP_SETTINGS
+        .read()
+        .map(|s| s.custom_launch.clone())
+        .unwrap_or_default();
+
+    let mut cmd = match build_command(profile, &template, folder, &abs_exe, &vars) {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            log::error!("Custom launch: {}", e);
+            return None;
+        }
+    };
+    #[cfg(target_os = "linux")]
+    if let Some(allow_network) = sandbox {
+        let home = super::sandbox::sandbox_home(thread_id);
+        cmd = match super::sandbox::sandbox_command(&cmd, folder, &home, allow_network) {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                // Never fall back to an unsandboxed launch
+                log::error!("Sandboxed launch failed: {}", e);
+                crate::app::errors_ui::append_error(format!("Sandboxed launch failed: {e}"));
+                return None;
+            }
+        };
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = sandbox;
+
+    log::info!(
+        "Custom launch ({}): {:?}",
+        profile.map(|p| p.name.as_str()).unwrap_or("global"),
+        cmd
+    );
+    match cmd.spawn() {
+        Ok(child) => {
+            log::info!("Launched game (custom): {}", abs_exe.to_string_lossy());
+            Some(child)
+        }
+        Err(e) => {
+            log::error!("Custom launch failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Start the game and return the spawned process (possibly a wrapper) for session tracking.
+/// Returns None when nothing was started and the folder was revealed instead.
 #[cfg(target_os = "windows")]
-fn run_executable(path: &Path) {
+fn run_executable(path: &Path) -> Option<Child> {
     use std::os::windows::process::CommandExt;
     const DETACHED_PROCESS: u32 = 0x00000008;
     const CREATE_NEW_CONSOLE: u32 = 0x00000010;
@@ -22,65 +97,6 @@ fn run_executable(path: &Path) {
         Err(_) => path.to_path_buf(),
     };
 
-    // Try custom launch template if provided (uses {{path}} placeholder)
-    {
-        let template = APP_SETTINGS
-            .read()
-            .map(|s| s.custom_launch.clone())
-            .unwrap_or_default();
-        if !template.trim().is_empty() {
-            let path_s = abs_exe.to_string_lossy().to_string();
-
-            let cmdline = template.replace("{{path}}", &format!("\"{}\"", path_s));
-
-            // Split command line into program and args respecting quotes (simple parser)
-            fn split_cmdline(s: &str) -> Vec<String> {
-                let mut out = Vec::new();
-                let mut cur = String::new();
-                let mut in_quotes = false;
-                for ch in s.chars() {
-                    match ch {
-                        '"' => in_quotes = !in_quotes,
-                        c if c.is_whitespace() && !in_quotes => {
-                            if !cur.is_empty() {
-                                out.push(cur.clone());
-                                cur.clear();
-                            }
-                        }
-                        _ => cur.push(ch),
-                    }
-                }
-                if !cur.is_empty() {
-                    out.push(cur);
-                }
-                out
-            }
-
-            let parts = split_cmdline(&cmdline);
-            if let Some((prog, args)) = parts.split_first() {
-                let mut child = std::process::Command::new(prog);
-                if let Some(d) = &dir {
-                    child.current_dir(d);
-                }
-                child.args(args);
-                log::info!("Custom launch (direct): {} {:?}", prog, args);
-                match child.spawn() {
-                    Ok(_) => {
-                        log::info!("Launched game (custom): {}", abs_exe.to_string_lossy());
-                        return;
-                    }
-                    Err(e) => {
-                        log::error!("Custom launch failed: {}", e);
-                        return;
-                    }
-                }
-            } else {
-                log::error!("Custom launch: empty command after parsing");
-                return;
-            }
-        }
-    }
-
     // Launch directly without invoking a shell to avoid cmd injection
     {
         let mut direct = std::process::Command::new(&abs_exe);
@@ -89,9 +105,9 @@ fn run_executable(path: &Path) {
         }
         direct.creation_flags(DETACHED_PROCESS | CREATE_NEW_CONSOLE);
         match direct.spawn() {
-            Ok(_) => {
+            Ok(child) => {
                 log::info!("Launched game (direct): {}", abs_exe.to_string_lossy());
-                return;
+                return Some(child);
             }
             Err(e) => {
                 log::warn!(
@@ -116,9 +132,9 @@ fn run_executable(path: &Path) {
         }
         log::info!("PS Start-Process: {}", ps_cmd);
         match pwsh.spawn() {
-            Ok(_) => {
+            Ok(child) => {
                 log::info!("Launched game (powershell): {}", abs_exe.to_string_lossy());
-                return;
+                return Some(child);
             }
             Err(e) => {
                 log::warn!(
@@ -134,6 +150,7 @@ fn run_executable(path: &Path) {
     if let Some(d) = &dir {
         reveal_in_file_manager(d);
     }
+    None
 }
 
 #[cfg(target_os = "windows")]
@@ -234,62 +251,130 @@ fn depth_from(root: &Path, path: &Path) -> Option<usize> {
     Some(rel.components().count())
 }
 
-/// Public: run a downloaded game by thread_id (Windows: try best .exe; others: open folder)
-pub fn run_downloaded_game(thread_id: u64) {
+/// Public: run a downloaded game by thread_id.
+/// The active launch profile (or the global custom launch template) is used when set, and on
+/// Linux the command is wrapped in the sandbox when enabled for the game;
+/// otherwise Windows starts the best .exe and other platforms open the folder.
+/// HTML games (`html_prefix`, or no executable but a web build) are served from a local
+/// HTTP server and opened in the browser.
+pub fn run_downloaded_game(thread_id: u64, title: &str, html_prefix: bool) {
+    // Double-launch guard: the game is still tracked as running
+    if super::running::is_game_running(thread_id) {
+        log::warn!("Game for thread {thread_id} is already running; launch ignored");
+        return;
+    }
+
     let folder = match downloaded_game_folder(thread_id) {
         Some(f) => f,
         None => return,
     };
+    let profile = active_launch_profile(thread_id);
+    let has_custom = profile.is_some()
+        || APP_SETTINGS
+            .read()
+            .map(|s| !s.custom_launch.trim().is_empty())
+            .unwrap_or(false);
+    // Sandbox toggle is only honoured on Linux
+    let sandbox: Option<bool> = if cfg!(target_os = "linux") {
+        crate::app::settings::with_settings(|st| {
+            st.downloaded_games
+                .iter()
+                .find(|g| g.thread_id == thread_id && g.sandboxed)
+                .map(|g| g.sandbox_network)
+        })
+    } else {
+        None
+    };
 
     #[cfg(target_os = "windows")]
-    {
+    let detected = {
         let recorded = downloaded_game_exe(thread_id).filter(|p| p.is_file());
         let best = find_exe_closest_to_root(&folder);
 
-        let chosen = match (recorded, best) {
+        match (recorded, best) {
             (Some(r), Some(b)) => {
                 let rd = depth_from(&folder, &r).unwrap_or(usize::MAX);
                 let bd = depth_from(&folder, &b).unwrap_or(usize::MAX);
-                if bd < rd { b } else { r }
-            }
-            (Some(r), None) => r,
-            (None, Some(b)) => b,
-            (None, None) => {
-                // Nothing found: open folder for manual start
-                reveal_in_file_manager(&folder);
-                return;
-            }
-        };
-
-        // Persist the chosen exe (cache or update if changed)
-        {
-            let current = downloaded_game_exe(thread_id);
-            if current.as_ref().map(|p| p != &chosen).unwrap_or(true) {
-                record_downloaded_game(thread_id, folder.clone(), Some(chosen.clone()));
+                Some(if bd < rd { b } else { r })
             }
+            (Some(r), None) => Some(r),
+            (None, Some(b)) => Some(b),
+            (None, None) => None,
         }
+    };
+    #[cfg(not(target_os = "windows"))]
+    let detected = downloaded_game_exe(thread_id).filter(|p| p.is_file());
 
-        {
-            let mut st = APP_SETTINGS.write().unwrap();
-            if let Some(game) = st
-                .downloaded_games
-                .iter_mut()
-                .find(|g| g.thread_id == thread_id)
-            {
-                if !game.has_been_launched {
-                    game.has_been_launched = true;
-                }
-            }
+    // Web builds break under file:// (CORS), serve them over localhost instead.
+    // An active launch profile always wins.
+    if profile.is_none()
+        && (html_prefix || detected.is_none())
+        && let Some(index) = find_html_entry(&folder)
+    {
+        mark_launched(thread_id);
+        serve_html_game(thread_id, index);
+        return;
+    }
+
+    let has_override = profile
+        .as_ref()
+        .is_some_and(|p| p.exe_override.is_some());
+    let chosen = match detected {
+        Some(d) => profile_exe(profile.as_ref(), &folder, &d),
+        None if has_override => profile_exe(profile.as_ref(), &folder, &folder),
+        None => {
+            // Nothing found: open folder for manual start
+            reveal_in_file_manager(&folder);
+            return;
         }
-        save_settings_to_disk();
+    };
 
-        run_executable(&chosen);
+    // Without a profile, custom template or sandbox non-Windows platforms just open the folder
+    if !cfg!(target_os = "windows") && !has_custom && sandbox.is_none() {
+        reveal_in_file_manager(&folder);
         return;
     }
 
-    #[cfg(not(target_os = "windows"))]
+    // Persist the detected exe (cache or update if changed); overrides stay in the profile
+    if !has_override {
+        let current = downloaded_game_exe(thread_id);
+        if current.as_ref().map(|p| p != &chosen).unwrap_or(true) {
+            record_downloaded_game(thread_id, folder.clone(), Some(chosen.clone()));
+        }
+    }
+
+    mark_launched(thread_id);
+
+    let child = if has_custom || sandbox.is_some() {
+        run_custom(profile.as_ref(), &folder, &chosen, title, thread_id, sandbox)
+    } else {
+        spawn_default(&chosen)
+    };
+    if let Some(child) = child {
+        track_session(thread_id, chosen, Some(child));
+    }
+}
+
+fn mark_launched(thread_id: u64) {
     {
-        // Non-Windows fallback
-        reveal_in_file_manager(&folder);
+        let mut st = APP_SETTINGS.write().unwrap();
+        if let Some(game) = st
+            .downloaded_games
+            .iter_mut()
+            .find(|g| g.thread_id == thread_id)
+        {
+            game.has_been_launched = true;
+        }
     }
+    save_settings_to_disk();
+}
+
+#[cfg(target_os = "windows")]
+fn spawn_default(exe: &Path) -> Option<Child> {
+    run_executable(exe)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn spawn_default(_exe: &Path) -> Option<Child> {
+    None
 }
@@ -0,0 +1,160 @@
+// Registry of games launched from the app and still running.
+// The session tracker registers an entry on launch and removes it when the game exits;
+// the UI reads it to show a running indicator, guard against double launches and stop a game.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+
+use super::session::find_processes;
+
+#[derive(Debug, Clone)]
+pub struct RunningGame {
+    /// PID of the spawned process (or of the game itself once found by the fallback watch)
+    pub pid: Option<u32>,
+    pub exe: PathBuf,
+    pub started: Instant,
+}
+
+lazy_static! {
+    static ref RUNNING_GAMES: Mutex<HashMap<u64, RunningGame>> = Mutex::new(HashMap::new());
+}
+
+pub(super) fn register(thread_id: u64, exe: PathBuf, pid: Option<u32>) {
+    RUNNING_GAMES.lock().unwrap().insert(
+        thread_id,
+        RunningGame {
+            pid,
+            exe,
+            started: Instant::now(),
+        },
+    );
+}
+
+pub(super) fn set_pid(thread_id: u64, pid: u32) {
+    if let Some(game) = RUNNING_GAMES.lock().unwrap().get_mut(&thread_id) {
+        game.pid = Some(pid);
+    }
+}
+
+pub(super) fn unregister(thread_id: u64) {
+    RUNNING_GAMES.lock().unwrap().remove(&thread_id);
+}
+
+pub fn is_game_running(thread_id: u64) -> bool {
+    RUNNING_GAMES.lock().unwrap().contains_key(&thread_id)
+}
+
+pub fn running_game(thread_id: u64) -> Option<RunningGame> {
+    RUNNING_GAMES.lock().unwrap().get(&thread_id).cloned()
+}
+
+/// Terminate a running game together with its child processes.
+/// The registry entry is removed by the session tracker once the process is gone.
+pub fn stop_game(thread_id: u64) {
+    // HTML games: stopping the local server ends the session
+    if super::html_server::stop_server(thread_id) {
+        log::info!("Stopping HTML game server for thread {thread_id}");
+        return;
+    }
+    let Some(game) = running_game(thread_id) else {
+        return;
+    };
+    let mut pids: Vec<u32> = game.pid.into_iter().collect();
+    // Wrappers may have exited already; also hit the game executable, but only processes
+    // running from the game folder (the file name alone matches other games too)
+    for pid in find_processes(&game.exe) {
+        if !pids.contains(&pid) {
+            pids.push(pid);
+        }
+    }
+    log::info!("Stopping game for thread {thread_id} (pids: {pids:?})");
+    for pid in pids {
+        kill_process_tree(pid);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn kill_process_tree(pid: u32) {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let res = std::process::Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .creation_flags(CREATE_NO_WINDOW)
+        .status();
+    if let Err(e) = res {
+        log::warn!("taskkill for pid {pid} failed: {e}");
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn kill_process_tree(pid: u32) {
+    // Children first so they are not re-parented before we get to them
+    let mut pids = descendants(pid);
+    pids.push(pid);
+    for pid in pids {
+        let res = std::process::Command::new("kill")
+            .args(["-TERM", &pid.to_string()])
+            .status();
+        if let Err(e) = res {
+            log::warn!("kill for pid {pid} failed: {e}");
+        }
+    }
+}
+
+/// All descendants of `pid`, deepest first (read from /proc/<pid>/stat parent ids).
+#[cfg(target_os = "linux")]
+fn descendants(pid: u32) -> Vec<u32> {
+    let mut parents: HashMap<u32, Vec<u32>> = HashMap::new();
+    if let Ok(entries) = std::fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let Some(child) = entry
+                .file_name()
+                .to_str()
+                .and_then(|s| s.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            let Ok(stat) = std::fs::read_to_string(entry.path().join("stat")) else {
+                continue;
+            };
+            // Format: pid (comm) state ppid ...; comm may contain spaces, so split after ')'
+            let ppid = stat
+                .rsplit_once(')')
+                .and_then(|(_, rest)| rest.split_whitespace().nth(1))
+                .and_then(|s| s.parse::<u32>().ok());
+            if let Some(ppid) = ppid {
+                parents.entry(ppid).or_default().push(child);
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut stack = vec![pid];
+    while let Some(p) = stack.pop() {
+        for &c in parents.get(&p).map(|v| v.as_slice()).unwrap_or(&[]) {
+            out.push(c);
+            stack.push(c);
+        }
+    }
+    out.reverse();
+    out
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn descendants(pid: u32) -> Vec<u32> {
+    std::process::Command::new("pgrep")
+        .args(["-P", &pid.to_string()])
+        .output()
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .filter_map(|l| l.trim().parse::<u32>().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,121 @@
+// Linux sandbox launching: wrap a prepared launch command in bubblewrap (preferred) or firejail.
+// The game gets a read-only view of the system, write access only to its own folder and a
+// per-game save dir (used as $HOME, so Wine prefixes and ~/.renpy saves land there), and no
+// network unless allowed for that game.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::app::settings::store::APP_SETTINGS;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SandboxTool {
+    Bwrap,
+    Firejail,
+}
+
+/// Per-game writable home for the sandbox: `<extract_dir>/.sandbox/<thread_id>`
+pub fn sandbox_home(thread_id: u64) -> PathBuf {
+    let extract_dir = APP_SETTINGS.read().unwrap().extract_dir.clone();
+    let base = std::fs::canonicalize(&extract_dir).unwrap_or(extract_dir);
+    base.join(".sandbox").join(thread_id.to_string())
+}
+
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|p| p.is_file())
+}
+
+fn detect_tool() -> Option<(SandboxTool, PathBuf)> {
+    find_in_path("bwrap")
+        .map(|p| (SandboxTool::Bwrap, p))
+        .or_else(|| find_in_path("firejail").map(|p| (SandboxTool::Firejail, p)))
+}
+
+/// Wrap `inner` so it runs inside the sandbox. Windows executables without a wrapper are
+/// started through `wine`. Fails when neither bwrap nor firejail is installed.
+pub fn sandbox_command(
+    inner: &Command,
+    game_dir: &Path,
+    home: &Path,
+    allow_network: bool,
+) -> Result<Command, String> {
+    let (tool, tool_path) =
+        detect_tool().ok_or_else(|| "neither bwrap nor firejail found in PATH".to_string())?;
+    std::fs::create_dir_all(home)
+        .map_err(|e| format!("create sandbox home {}: {e}", home.display()))?;
+
+    let game_dir = std::fs::canonicalize(game_dir).unwrap_or_else(|_| game_dir.to_path_buf());
+    let mut argv: Vec<OsString> = Vec::new();
+    let program = inner.get_program();
+    let is_windows_exe = Path::new(program)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("exe"));
+    if is_windows_exe {
+        argv.push("wine".into());
+    }
+    argv.push(program.to_os_string());
+    argv.extend(inner.get_args().map(|a| a.to_os_string()));
+    let cwd = inner
+        .get_current_dir()
+        .map(|d| d.to_path_buf())
+        .unwrap_or_else(|| game_dir.clone());
+
+    let mut cmd = Command::new(&tool_path);
+    match tool {
+        SandboxTool::Bwrap => {
+            cmd.args(["--ro-bind", "/", "/"])
+                .args(["--dev", "/dev"])
+                .args(["--proc", "/proc"])
+                .args(["--tmpfs", "/tmp"])
+                // Keep the X11 socket reachable after /tmp was replaced
+                .args(["--ro-bind-try", "/tmp/.X11-unix", "/tmp/.X11-unix"]);
+            // Hide the real home directory; the game folder is re-bound below if it lives there
+            if let Some(real_home) = std::env::var_os("HOME") {
+                cmd.arg("--tmpfs").arg(&real_home);
+            }
+            cmd.arg("--bind").arg(&game_dir).arg(&game_dir);
+            cmd.arg("--bind").arg(home).arg(home);
+            cmd.arg("--setenv").arg("HOME").arg(home);
+            cmd.args([
+                "--unshare-user-try",
+                "--unshare-pid",
+                "--unshare-uts",
+                "--unshare-cgroup-try",
+            ]);
+            if !allow_network {
+                cmd.arg("--unshare-net");
+            }
+            cmd.args(["--die-with-parent", "--new-session"]);
+            cmd.arg("--chdir").arg(&cwd);
+        }
+        SandboxTool::Firejail => {
+            cmd.args(["--quiet", "--noprofile", "--read-only=/"]);
+            let mut rw = OsString::from("--read-write=");
+            rw.push(&game_dir);
+            cmd.arg(rw);
+            let mut rw_home = OsString::from("--read-write=");
+            rw_home.push(home);
+            cmd.arg(rw_home);
+            let mut env_home = OsString::from("--env=HOME=");
+            env_home.push(home);
+            cmd.arg(env_home);
+            if !allow_network {
+                cmd.arg("--net=none");
+            }
+            cmd.current_dir(&cwd);
+        }
+    }
+    for (k, v) in inner.get_envs() {
+        if let Some(v) = v {
+            cmd.env(k, v);
+        }
+    }
+    cmd.arg("--");
+    cmd.args(argv);
+    Ok(cmd)
+}
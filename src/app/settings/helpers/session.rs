@@ -0,0 +1,311 @@
+// Play session tracking: wait for a launched game to exit and accumulate playtime.
+// When the spawned process is only a launcher/wrapper (custom launch, PowerShell
+// Start-Process, Sandboxie etc.) it exits almost immediately; in that case we fall back
+// to a best-effort watch that looks for the game executable, running from the game folder,
+// in the process list.
+// While a session is tracked the game is listed in the running-games registry.
+
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+use super::running;
+use crate::app::settings::store::record_play_session;
+
+/// A child that exits faster than this is treated as a wrapper, not the game itself.
+const WRAPPER_GRACE: Duration = Duration::from_secs(5);
+/// How long to wait for the real game process to show up after the wrapper exited.
+const APPEAR_TIMEOUT: Duration = Duration::from_secs(15);
+/// Poll interval for the process-list fallback.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn now_unix() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Track a play session on a background thread and persist it once the game exits.
+/// `child` is the spawned process if any; `exe` is the game executable used for the fallback watch.
+pub fn track_session(thread_id: u64, exe: PathBuf, child: Option<Child>) {
+    // Register synchronously so a second click in the same frame already sees the game as running
+    running::register(thread_id, exe.clone(), child.as_ref().map(|c| c.id()));
+    let spawn_res = std::thread::Builder::new()
+        .name(format!("session-{thread_id}"))
+        .spawn(move || {
+            let started = Instant::now();
+            let mut last_seen = started;
+            let mut needs_watch = true;
+
+            if let Some(mut child) = child {
+                match child.wait() {
+                    Ok(status) => match status.code() {
+                        Some(code) => {
+                            log::info!(
+                                "Game process for thread {thread_id} exited with code {code}"
+                            )
+                        }
+                        None => log::info!("Game process for thread {thread_id} exited: {status}"),
+                    },
+                    Err(e) => {
+                        log::warn!("Waiting for game process of thread {thread_id} failed: {e}")
+                    }
+                }
+                last_seen = Instant::now();
+                needs_watch = started.elapsed() < WRAPPER_GRACE;
+            }
+
+            if needs_watch && let Some(seen) = watch_by_image(thread_id, &exe) {
+                last_seen = seen;
+            }
+
+            running::unregister(thread_id);
+            let duration = last_seen.duration_since(started).as_secs();
+            record_play_session(thread_id, now_unix(), duration);
+        });
+    if let Err(e) = spawn_res {
+        running::unregister(thread_id);
+        log::error!("Failed to start session tracker for thread {thread_id}: {e}");
+    }
+}
+
+/// Wait for the executable to appear in the process list, then poll until it disappears.
+/// Only processes running from the game folder count, so an unrelated "Game.exe" doesn't keep
+/// the session open.
+/// Returns the last moment it was seen running, or None if it never showed up.
+/// The first matching pid is stored in the running-games registry so the game can be stopped.
+fn watch_by_image(thread_id: u64, exe: &Path) -> Option<Instant> {
+    let appear_deadline = Instant::now() + APPEAR_TIMEOUT;
+    let mut last_seen: Option<Instant> = None;
+    loop {
+        let pids = find_processes(exe);
+        if let Some(&pid) = pids.first() {
+            if last_seen.is_none() {
+                running::set_pid(thread_id, pid);
+            }
+            last_seen = Some(Instant::now());
+        } else if last_seen.is_some() || Instant::now() >= appear_deadline {
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    last_seen
+}
+
+/// Folder a process must run from to count as the game: the executable's folder.
+fn game_folder(exe: &Path) -> Option<PathBuf> {
+    let folder = exe.parent()?;
+    Some(std::fs::canonicalize(folder).unwrap_or_else(|_| folder.to_path_buf()))
+}
+
+/// Whether `path` is inside `folder`. Windows paths compare case-insensitively and without the
+/// `\\?\` prefix `canonicalize` adds.
+fn is_inside(path: &Path, folder: &Path) -> bool {
+    if cfg!(target_os = "windows") {
+        let norm = |p: &Path| {
+            let p = p.to_string_lossy().replace('/', "\\").to_lowercase();
+            p.strip_prefix(r"\\?\").map(str::to_string).unwrap_or(p)
+        };
+        let (path, folder) = (norm(path), norm(folder));
+        let folder = folder.trim_end_matches('\\');
+        path.strip_prefix(folder)
+            .is_some_and(|rest| rest.starts_with('\\'))
+    } else {
+        path.starts_with(folder)
+    }
+}
+
+/// Running processes of the game: named like `exe` and running from inside its folder
+/// (executable path or working directory). The file name alone is never enough, other games
+/// ship a "Game.exe" too.
+/// Uses a Toolhelp snapshot: it is polled every few seconds while the game runs, so no
+/// helper process is started.
+#[cfg(target_os = "windows")]
+pub fn find_processes(exe: &Path) -> Vec<u32> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+        TH32CS_SNAPPROCESS,
+    };
+
+    let (Some(name), Some(folder)) = (exe.file_name().and_then(|s| s.to_str()), game_folder(exe))
+    else {
+        return Vec::new();
+    };
+    let name = name.to_lowercase();
+    let own_pid = std::process::id();
+    let mut pids = Vec::new();
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return pids;
+        }
+        let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        let mut ok = Process32FirstW(snapshot, &mut entry);
+        while ok != 0 {
+            let len = entry
+                .szExeFile
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(entry.szExeFile.len());
+            let pid = entry.th32ProcessID;
+            if pid != own_pid
+                && String::from_utf16_lossy(&entry.szExeFile[..len]).to_lowercase() == name
+                && image_path(pid).is_some_and(|p| is_inside(&p, &folder))
+            {
+                pids.push(pid);
+            }
+            ok = Process32NextW(snapshot, &mut entry);
+        }
+        CloseHandle(snapshot);
+    }
+    pids
+}
+
+/// Executable path of a process; None for processes we may not inspect.
+#[cfg(target_os = "windows")]
+fn image_path(pid: u32) -> Option<PathBuf> {
+    use std::os::windows::ffi::OsStringExt;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+        QueryFullProcessImageNameW,
+    };
+
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+        let mut buf = [0u16; 1024];
+        let mut len = buf.len() as u32;
+        let ok =
+            QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buf.as_mut_ptr(), &mut len);
+        CloseHandle(process);
+        (ok != 0).then(|| PathBuf::from(std::ffi::OsString::from_wide(&buf[..len as usize])))
+    }
+}
+
+/// Running processes of the game: named like `exe` and running from inside its folder
+/// (executable path or working directory). The file name alone is never enough, other games
+/// ship a "Game.exe" too. Scans /proc so that games started through Wine or a wrapper are matched
+/// by command line and working directory.
+#[cfg(target_os = "linux")]
+pub fn find_processes(exe: &Path) -> Vec<u32> {
+    let (Some(name), Some(folder)) = (exe.file_name(), game_folder(exe)) else {
+        return Vec::new();
+    };
+    let own_pid = std::process::id();
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+            if pid == own_pid {
+                return None;
+            }
+            let proc_dir = entry.path();
+            let exe_path = std::fs::read_link(proc_dir.join("exe")).ok();
+            if exe_path
+                .as_deref()
+                .is_some_and(|p| p.file_name() == Some(name) && is_inside(p, &folder))
+            {
+                return Some(pid);
+            }
+            // Wine and interpreters: the game is the first command line argument and runs from
+            // its folder
+            let cmdline_matches = std::fs::read(proc_dir.join("cmdline"))
+                .ok()
+                .and_then(|raw| {
+                    let first = raw.split(|b| *b == 0).next()?.to_vec();
+                    let first = String::from_utf8_lossy(&first).replace('\\', "/");
+                    Some(first.rsplit('/').next().map(std::ffi::OsStr::new) == Some(name))
+                })
+                .unwrap_or(false);
+            let cwd_inside =
+                std::fs::read_link(proc_dir.join("cwd")).is_ok_and(|cwd| is_inside(&cwd, &folder));
+            (cmdline_matches && cwd_inside).then_some(pid)
+        })
+        .collect()
+}
+
+/// Running processes of the game: named like `exe` and running from inside its folder. The file
+/// name alone is never enough, other games ship a "Game.exe" too.
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn find_processes(exe: &Path) -> Vec<u32> {
+    let (Some(name), Some(folder)) = (exe.file_name().and_then(|s| s.to_str()), game_folder(exe))
+    else {
+        return Vec::new();
+    };
+    let Ok(output) = std::process::Command::new("pgrep")
+        .arg("-x")
+        .arg(name)
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().parse::<u32>().ok())
+        .filter(|pid| process_paths(*pid).iter().any(|p| is_inside(p, &folder)))
+        .collect()
+}
+
+/// Executable path (`ps` shows the full path on macOS) and working directory (`lsof`) of a pid.
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn process_paths(pid: u32) -> Vec<PathBuf> {
+    let pid = pid.to_string();
+    let run = |cmd: &str, args: &[&str]| {
+        std::process::Command::new(cmd)
+            .args(args)
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+            .unwrap_or_default()
+    };
+    let mut paths = Vec::new();
+    let comm = run("ps", &["-o", "comm=", "-p", &pid]);
+    if comm.trim().starts_with('/') {
+        paths.push(PathBuf::from(comm.trim()));
+    }
+    // -Fn prints the cwd as a line starting with "n"
+    let lsof = run("lsof", &["-a", "-p", &pid, "-d", "cwd", "-Fn"]);
+    paths.extend(
+        lsof.lines()
+            .filter_map(|l| l.strip_prefix('n'))
+            .map(PathBuf::from),
+    );
+    paths
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_processes_ignores_same_name_elsewhere() {
+        let root = std::env::temp_dir().join(format!("f95-session-{}", uuid::Uuid::new_v4()));
+        let spawn = |dir: &str| {
+            let folder = root.join(dir);
+            std::fs::create_dir_all(&folder).unwrap();
+            let exe = folder.join("Game");
+            std::fs::copy("/bin/sleep", &exe).unwrap();
+            let child = std::process::Command::new(&exe).arg("30").spawn().unwrap();
+            (exe, child)
+        };
+        let (exe, mut ours) = spawn("ours");
+        let (_, mut other) = spawn("other");
+        std::thread::sleep(Duration::from_millis(200));
+
+        let found = find_processes(&exe);
+        ours.kill().unwrap();
+        other.kill().unwrap();
+        let _ = (ours.wait(), other.wait());
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(found, vec![ours.id()]);
+    }
+}
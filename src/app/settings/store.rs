@@ -26,6 +26,25 @@ pub struct Bookmark {
     pub color: Option<[u8; 3]>,
 }
 
+// Named per-game launch configuration. String fields accept shell-style quoting and the
+// {{path}}, {{dir}}, {{title}} and {{thread_id}} placeholders.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct LaunchProfile {
+    pub name: String,
+    // Executable to start instead of the detected one (relative paths are resolved against the game folder)
+    #[serde(default)]
+    pub exe_override: Option<PathBuf>,
+    #[serde(default)]
+    pub args: String,
+    #[serde(default)]
+    pub env: Vec<(String, String)>,
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
+    // Wrapper command line, e.g. `wine {{path}}`; overrides the global custom launch template
+    #[serde(default)]
+    pub wrapper: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadedGame {
     pub thread_id: u64,
@@ -35,6 +54,23 @@ pub struct DownloadedGame {
     pub has_been_launched: bool,
     #[serde(default)]
     pub bookmark_ids: Vec<String>,
+    // Accumulated playtime across all tracked sessions, in seconds
+    #[serde(default)]
+    pub total_playtime_secs: u64,
+    // Unix timestamp (seconds) of the end of the last tracked session
+    #[serde(default)]
+    pub last_played: Option<i64>,
+    #[serde(default)]
+    pub launch_profiles: Vec<LaunchProfile>,
+    // Name of the profile used by Play; None runs the game with default settings
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    // Linux only: run the game inside bubblewrap/firejail
+    #[serde(default)]
+    pub sandboxed: bool,
+    // Allow network access inside the sandbox (off by default)
+    #[serde(default)]
+    pub sandbox_network: bool,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -62,6 +98,125 @@ impl Default for UpdateCheckFrequency {
     }
 }
 
+// Per-hosting download outcomes, used to demote hostings that keep failing
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HostingStats {
+    #[serde(default)]
+    pub successes: u32,
+    #[serde(default)]
+    pub failures: u32,
+    // Failures since the last success
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    #[serde(default)]
+    pub last_failure: Option<i64>,
+}
+
+// Platform build to download, in the user's order of preference
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DownloadPlatform {
+    #[serde(rename = "windows")]
+    Windows,
+    #[serde(rename = "linux")]
+    Linux,
+    #[serde(rename = "mac")]
+    Mac,
+    #[serde(rename = "android")]
+    Android,
+}
+
+impl DownloadPlatform {
+    pub const ALL: [DownloadPlatform; 4] = [
+        DownloadPlatform::Windows,
+        DownloadPlatform::Linux,
+        DownloadPlatform::Mac,
+        DownloadPlatform::Android,
+    ];
+
+    /// Platform of the running OS, used when no order is configured
+    pub fn host() -> Self {
+        if cfg!(target_os = "linux") {
+            DownloadPlatform::Linux
+        } else if cfg!(target_os = "macos") {
+            DownloadPlatform::Mac
+        } else if cfg!(target_os = "android") {
+            DownloadPlatform::Android
+        } else {
+            DownloadPlatform::Windows
+        }
+    }
+
+    pub fn flag(self) -> crate::parser::game_info::Platform {
+        use crate::parser::game_info::Platform;
+        match self {
+            DownloadPlatform::Windows => Platform::WINDOWS,
+            DownloadPlatform::Linux => Platform::LINUX,
+            DownloadPlatform::Mac => Platform::MAC,
+            DownloadPlatform::Android => Platform::ANDROID,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DownloadPlatform::Windows => "Windows",
+            DownloadPlatform::Linux => "Linux",
+            DownloadPlatform::Mac => "Mac",
+            DownloadPlatform::Android => "Android",
+        }
+    }
+
+    /// "Windows/Linux" for a parsed platform set; "Other" when none is known
+    pub fn labels(flags: crate::parser::game_info::Platform) -> String {
+        let labels: Vec<&str> = Self::ALL
+            .iter()
+            .filter(|p| flags.contains(p.flag()))
+            .map(|p| p.label())
+            .collect();
+        if labels.is_empty() {
+            "Other".to_string()
+        } else {
+            labels.join("/")
+        }
+    }
+}
+
+// Code page for archive entry names that aren't UTF-8 (old zips, Japanese/Chinese/Russian tools)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ArchiveNameEncoding {
+    #[serde(rename = "auto")]
+    #[default]
+    Auto,
+    #[serde(rename = "cp437")]
+    Cp437,
+    #[serde(rename = "shift_jis")]
+    ShiftJis,
+    #[serde(rename = "gbk")]
+    Gbk,
+    #[serde(rename = "cp1251")]
+    Cp1251,
+}
+
+impl ArchiveNameEncoding {
+    pub const ALL: [ArchiveNameEncoding; 5] = [
+        ArchiveNameEncoding::Auto,
+        ArchiveNameEncoding::Cp437,
+        ArchiveNameEncoding::ShiftJis,
+        ArchiveNameEncoding::Gbk,
+        ArchiveNameEncoding::Cp1251,
+    ];
+
+    /// Code page name; None for Auto, which is localized
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            ArchiveNameEncoding::Auto => None,
+            ArchiveNameEncoding::Cp437 => Some("CP437 (DOS)"),
+            ArchiveNameEncoding::ShiftJis => Some("Shift-JIS"),
+            ArchiveNameEncoding::Gbk => Some("GBK"),
+            ArchiveNameEncoding::Cp1251 => Some("CP1251"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub temp_dir: PathBuf,
@@ -128,6 +283,72 @@ pub struct AppSettings {
     pub bookmarks_visible_on_cover: u8,
     #[serde(default)]
     pub filter_bookmarks: Vec<String>,
+    // Keep the original archive after "Install from file". Default: true (the file is the user's)
+    #[serde(default = "default_keep_local_archive")]
+    pub keep_local_archive: bool,
+    // Preferred platforms for downloads, first match wins. Empty = the running OS only
+    #[serde(default)]
+    pub platform_order: Vec<DownloadPlatform>,
+    // Hostings (by domain, e.g. "pixeldrain.com") tried first, in order; others keep page order
+    #[serde(default)]
+    pub hosting_order: Vec<String>,
+    // Hostings that are never tried
+    #[serde(default)]
+    pub hosting_blacklist: Vec<String>,
+    #[serde(default)]
+    pub hosting_stats: std::collections::HashMap<String, HostingStats>,
+    // Worker threads for zip extraction. 0 = automatic (CPU count, capped)
+    #[serde(default)]
+    pub extract_threads: u8,
+    // Memory ceiling for 7z decompression in MiB; archives needing more are refused. 0 = no limit
+    #[serde(default = "default_sevenz_memory_limit_mb")]
+    pub sevenz_memory_limit_mb: u32,
+    // Encoding of non-UTF-8 entry names in archives
+    #[serde(default)]
+    pub archive_name_encoding: ArchiveNameEncoding,
+    // Keep extracted archives in archive_library_dir (by thread id and version) for offline
+    // reinstall and repair
+    #[serde(default)]
+    pub archive_library: bool,
+    #[serde(default = "default_archive_library_dir")]
+    pub archive_library_dir: PathBuf,
+    // Oldest archives are removed past this size in GiB. 0 = no limit
+    #[serde(default)]
+    pub archive_library_max_gb: u32,
+    // Download speed cap for all downloads together in KiB/s. 0 = no limit
+    #[serde(default)]
+    pub bandwidth_limit_kib: u32,
+    // New downloads only start inside the window below (minutes after local midnight)
+    #[serde(default)]
+    pub download_schedule: bool,
+    #[serde(default = "default_download_schedule_start")]
+    pub download_schedule_start: u16,
+    #[serde(default = "default_download_schedule_end")]
+    pub download_schedule_end: u16,
+    // Parallel connections per download for servers that accept Range requests. 1 = one stream
+    #[serde(default = "default_download_connections")]
+    pub download_connections: u8,
+    // Site endpoints; empty = the default f95zone address. F95_BASE_URL and the other
+    // environment variables take precedence (see net::endpoints)
+    #[serde(default)]
+    pub site_base_url: String,
+    #[serde(default)]
+    pub site_attachments_url: String,
+    #[serde(default)]
+    pub site_preview_url: String,
+    #[serde(default)]
+    pub site_masked_path: String,
+    // Proxy for all requests (http://, https://, socks5://, socks5h://). Empty = direct
+    #[serde(default)]
+    pub proxy_url: String,
+    // File hostings use hosting_proxy_url instead of proxy_url (empty = direct)
+    #[serde(default)]
+    pub hosting_proxy_separate: bool,
+    #[serde(default)]
+    pub hosting_proxy_url: String,
+    // Empty = built-in default (net::USER_AGENT)
+    #[serde(default)]
+    pub user_agent: String,
 }
 
 impl Persistable for AppSettings {}
@@ -161,6 +382,30 @@ impl Default for AppSettings {
             default_bookmark_color: default_bookmark_color(),
             bookmarks_visible_on_cover: default_bookmarks_visible(),
             filter_bookmarks: Vec::new(),
+            keep_local_archive: default_keep_local_archive(),
+            platform_order: Vec::new(),
+            hosting_order: Vec::new(),
+            hosting_blacklist: Vec::new(),
+            hosting_stats: std::collections::HashMap::new(),
+            extract_threads: 0,
+            sevenz_memory_limit_mb: default_sevenz_memory_limit_mb(),
+            archive_name_encoding: ArchiveNameEncoding::Auto,
+            archive_library: false,
+            archive_library_dir: default_archive_library_dir(),
+            archive_library_max_gb: 0,
+            bandwidth_limit_kib: 0,
+            download_schedule: false,
+            download_schedule_start: default_download_schedule_start(),
+            download_schedule_end: default_download_schedule_end(),
+            download_connections: default_download_connections(),
+            site_base_url: String::new(),
+            site_attachments_url: String::new(),
+            site_preview_url: String::new(),
+            site_masked_path: String::new(),
+            proxy_url: String::new(),
+            hosting_proxy_separate: false,
+            hosting_proxy_url: String::new(),
+            user_agent: String::new(),
         }
     }
 }
@@ -169,6 +414,30 @@ fn default_log_to_file() -> bool {
     true
 }
 
+fn default_keep_local_archive() -> bool {
+    true
+}
+
+fn default_sevenz_memory_limit_mb() -> u32 {
+    2048
+}
+
+fn default_download_schedule_start() -> u16 {
+    60
+}
+
+fn default_download_schedule_end() -> u16 {
+    7 * 60
+}
+
+fn default_download_connections() -> u8 {
+    1
+}
+
+fn default_archive_library_dir() -> PathBuf {
+    PathBuf::from("archives")
+}
+
 //// Serde helpers for language field to keep backward compatibility with older JSONs.
 fn deserialize_language_opt<'de, D>(
     deserializer: D,
@@ -293,6 +562,12 @@ pub fn record_downloaded_game(thread_id: u64, folder: PathBuf, exe_path: Option<
                 exe_path: exe_path.clone(),
                 has_been_launched: false,
                 bookmark_ids: Vec::new(),
+                total_playtime_secs: 0,
+                last_played: None,
+                launch_profiles: Vec::new(),
+                active_profile: None,
+                sandboxed: false,
+                sandbox_network: false,
             });
         }
         // Also clear any pending entry for this thread
@@ -301,6 +576,31 @@ pub fn record_downloaded_game(thread_id: u64, folder: PathBuf, exe_path: Option<
     save_settings_to_disk();
 }
 
+// Accumulate a finished play session into the game's record
+pub fn record_play_session(thread_id: u64, ended_at: i64, duration_secs: u64) {
+    {
+        let mut st = APP_SETTINGS.write().unwrap();
+        if let Some(game) = st
+            .downloaded_games
+            .iter_mut()
+            .find(|g| g.thread_id == thread_id)
+        {
+            game.has_been_launched = true;
+            game.total_playtime_secs = game.total_playtime_secs.saturating_add(duration_secs);
+            game.last_played = Some(ended_at);
+            log::info!(
+                "Recorded play session for thread {}: {}s (total {}s)",
+                thread_id,
+                duration_secs,
+                game.total_playtime_secs
+            );
+        } else {
+            return;
+        }
+    }
+    save_settings_to_disk();
+}
+
 // Mark a thread as hidden (adds its thread_id to settings and saves to disk)
 pub fn hide_thread(thread_id: u64) {
     {
@@ -515,6 +815,121 @@ pub fn get_game_bookmarks(thread_id: u64) -> Vec<Bookmark> {
     }
 }
 
+pub fn get_launch_profiles(thread_id: u64) -> Vec<LaunchProfile> {
+    let st = APP_SETTINGS.read().unwrap();
+    st.downloaded_games
+        .iter()
+        .find(|g| g.thread_id == thread_id)
+        .map(|g| g.launch_profiles.clone())
+        .unwrap_or_default()
+}
+
+pub fn set_launch_profiles(thread_id: u64, profiles: Vec<LaunchProfile>) {
+    {
+        let mut st = APP_SETTINGS.write().unwrap();
+        if let Some(game) = st
+            .downloaded_games
+            .iter_mut()
+            .find(|g| g.thread_id == thread_id)
+        {
+            // Drop the selection if its profile was removed or renamed
+            let removed = game
+                .active_profile
+                .as_ref()
+                .is_some_and(|active| !profiles.iter().any(|p| &p.name == active));
+            if removed {
+                game.active_profile = None;
+            }
+            game.launch_profiles = profiles;
+        }
+    }
+    save_settings_to_disk();
+}
+
+pub fn set_active_launch_profile(thread_id: u64, name: Option<String>) {
+    {
+        let mut st = APP_SETTINGS.write().unwrap();
+        if let Some(game) = st
+            .downloaded_games
+            .iter_mut()
+            .find(|g| g.thread_id == thread_id)
+        {
+            game.active_profile = name;
+        }
+    }
+    save_settings_to_disk();
+}
+
+pub fn set_game_sandbox(thread_id: u64, sandboxed: bool, allow_network: bool) {
+    {
+        let mut st = APP_SETTINGS.write().unwrap();
+        if let Some(game) = st
+            .downloaded_games
+            .iter_mut()
+            .find(|g| g.thread_id == thread_id)
+        {
+            game.sandboxed = sandboxed;
+            game.sandbox_network = allow_network;
+        }
+    }
+    save_settings_to_disk();
+}
+
+pub fn set_keep_local_archive(keep: bool) {
+    {
+        let mut st = APP_SETTINGS.write().unwrap();
+        st.keep_local_archive = keep;
+    }
+    save_settings_to_disk();
+}
+
+/// Record whether a download from `hosting` went through.
+pub fn record_hosting_result(hosting: &str, success: bool) {
+    if hosting.is_empty() {
+        return;
+    }
+    {
+        let mut st = APP_SETTINGS.write().unwrap();
+        let stats = st.hosting_stats.entry(hosting.to_string()).or_default();
+        if success {
+            stats.successes += 1;
+            stats.consecutive_failures = 0;
+        } else {
+            stats.failures += 1;
+            stats.consecutive_failures += 1;
+            stats.last_failure = Some(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0),
+            );
+        }
+    }
+    save_settings_to_disk();
+}
+
+pub fn reset_hosting_stats() {
+    {
+        let mut st = APP_SETTINGS.write().unwrap();
+        st.hosting_stats.clear();
+    }
+    save_settings_to_disk();
+}
+
+/// Profile selected for Play, if any
+pub fn active_launch_profile(thread_id: u64) -> Option<LaunchProfile> {
+    let st = APP_SETTINGS.read().unwrap();
+    let game = st
+        .downloaded_games
+        .iter()
+        .find(|g| g.thread_id == thread_id)?;
+    let active = game.active_profile.as_ref()?;
+    game.launch_profiles
+        .iter()
+        .find(|p| &p.name == active)
+        .cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -543,6 +958,17 @@ mod tests {
             exe_path: Some(PathBuf::from("test.exe")),
             has_been_launched: true,
             bookmark_ids: vec!["bookmark-1".to_string(), "bookmark-2".to_string()],
+            total_playtime_secs: 3600,
+            last_played: Some(1_700_000_000),
+            launch_profiles: vec![LaunchProfile {
+                name: "with mod".to_string(),
+                args: "--mod \"My Mod\"".to_string(),
+                env: vec![("LANG".to_string(), "en_US.UTF-8".to_string())],
+                ..Default::default()
+            }],
+            active_profile: Some("with mod".to_string()),
+            sandboxed: true,
+            sandbox_network: false,
         };
 
         let json = serde_json::to_string(&game).expect("Failed to serialize");
@@ -550,6 +976,11 @@ mod tests {
 
         assert_eq!(game.thread_id, decoded.thread_id);
         assert_eq!(game.bookmark_ids, decoded.bookmark_ids);
+        assert_eq!(game.total_playtime_secs, decoded.total_playtime_secs);
+        assert_eq!(game.last_played, decoded.last_played);
+        assert_eq!(game.launch_profiles, decoded.launch_profiles);
+        assert_eq!(game.active_profile, decoded.active_profile);
+        assert_eq!(game.sandboxed, decoded.sandboxed);
     }
 
     #[test]
@@ -567,6 +998,12 @@ mod tests {
 
         assert_eq!(game.thread_id, 12345);
         assert!(game.bookmark_ids.is_empty());
+        assert_eq!(game.total_playtime_secs, 0);
+        assert!(game.last_played.is_none());
+        assert!(game.launch_profiles.is_empty());
+        assert!(game.active_profile.is_none());
+        assert!(!game.sandboxed);
+        assert!(!game.sandbox_network);
     }
 
     #[test]
@@ -604,5 +1041,29 @@ mod tests {
         assert_eq!(settings.default_bookmark_color, [60, 120, 200]);
         assert_eq!(settings.bookmarks_visible_on_cover, 3);
         assert!(settings.filter_bookmarks.is_empty());
+        assert!(settings.keep_local_archive);
+        assert!(settings.platform_order.is_empty());
+        assert!(settings.hosting_order.is_empty());
+        assert!(settings.hosting_blacklist.is_empty());
+        assert!(settings.hosting_stats.is_empty());
+        assert_eq!(settings.extract_threads, 0);
+        assert_eq!(settings.sevenz_memory_limit_mb, 2048);
+        assert_eq!(settings.archive_name_encoding, ArchiveNameEncoding::Auto);
+        assert!(!settings.archive_library);
+        assert_eq!(settings.archive_library_dir, PathBuf::from("archives"));
+        assert_eq!(settings.archive_library_max_gb, 0);
+        assert_eq!(settings.bandwidth_limit_kib, 0);
+        assert!(!settings.download_schedule);
+        assert_eq!(settings.download_schedule_start, 60);
+        assert_eq!(settings.download_schedule_end, 420);
+        assert_eq!(settings.download_connections, 1);
+        assert!(settings.site_base_url.is_empty());
+        assert!(settings.site_attachments_url.is_empty());
+        assert!(settings.site_preview_url.is_empty());
+        assert!(settings.site_masked_path.is_empty());
+        assert!(settings.proxy_url.is_empty());
+        assert!(!settings.hosting_proxy_separate);
+        assert!(settings.hosting_proxy_url.is_empty());
+        assert!(settings.user_agent.is_empty());
     }
 }
@@ -5,7 +5,10 @@ use std::path::PathBuf;
 use std::sync::mpsc;
 
 use super::migrate;
-use super::store::{save_settings_to_disk, AppSettings, APP_SETTINGS, LoadingAnim, UpdateCheckFrequency};
+use super::store::{
+    save_settings_to_disk, AppSettings, ArchiveNameEncoding, DownloadPlatform, APP_SETTINGS,
+    LoadingAnim, UpdateCheckFrequency,
+};
 use crate::views::filters::items::{prefixes_menu::prefixes_picker, tags_menu::tags_picker};
 
 type MigratedGame = (u64, PathBuf, Option<PathBuf>);
@@ -26,7 +29,13 @@ pub struct SettingsUiState {
     temp_dir_input: String,
     extract_dir_input: String,
     cache_dir_input: String,
+    archive_library_input: bool,
+    archive_library_dir_input: String,
+    archive_library_max_gb_input: u32,
     custom_launch_input: String,
+    platform_order_input: Vec<DownloadPlatform>,
+    hosting_order_input: Vec<String>,
+    hosting_blacklist_input: Vec<String>,
     cache_on_download_input: bool,
     language_input: Option<crate::localization::SupportedLang>,
     loading_anim_input: LoadingAnim,
@@ -36,6 +45,22 @@ pub struct SettingsUiState {
     classic_library_toggle_input: bool,
     default_bookmark_color_input: [u8; 3],
     bookmarks_visible_on_cover_input: u8,
+    extract_threads_input: u8,
+    sevenz_memory_limit_input: u32,
+    archive_name_encoding_input: ArchiveNameEncoding,
+    bandwidth_limit_input: u32,
+    download_schedule_input: bool,
+    download_schedule_start_input: u16,
+    download_schedule_end_input: u16,
+    download_connections_input: u8,
+    site_base_url_input: String,
+    site_attachments_url_input: String,
+    site_preview_url_input: String,
+    site_masked_path_input: String,
+    proxy_url_input: String,
+    hosting_proxy_separate_input: bool,
+    hosting_proxy_url_input: String,
+    user_agent_input: String,
     move_confirm_open: bool,
     pending_move: Option<PendingMoveState>,
     warn_tags_input: Vec<u32>,
@@ -59,7 +84,13 @@ impl Default for SettingsUiState {
             temp_dir_input: String::new(),
             extract_dir_input: String::new(),
             cache_dir_input: String::new(),
+            archive_library_input: false,
+            archive_library_dir_input: String::new(),
+            archive_library_max_gb_input: 0,
             custom_launch_input: String::new(),
+            platform_order_input: Vec::new(),
+            hosting_order_input: Vec::new(),
+            hosting_blacklist_input: Vec::new(),
             cache_on_download_input: false,
             language_input: None,
             loading_anim_input: LoadingAnim::BottomBar,
@@ -69,6 +100,22 @@ impl Default for SettingsUiState {
             classic_library_toggle_input: false,
             default_bookmark_color_input: [60, 120, 200],
             bookmarks_visible_on_cover_input: 3,
+            extract_threads_input: 0,
+            sevenz_memory_limit_input: 2048,
+            archive_name_encoding_input: ArchiveNameEncoding::Auto,
+            bandwidth_limit_input: 0,
+            download_schedule_input: false,
+            download_schedule_start_input: 60,
+            download_schedule_end_input: 7 * 60,
+            download_connections_input: 1,
+            site_base_url_input: String::new(),
+            site_attachments_url_input: String::new(),
+            site_preview_url_input: String::new(),
+            site_masked_path_input: String::new(),
+            proxy_url_input: String::new(),
+            hosting_proxy_separate_input: false,
+            hosting_proxy_url_input: String::new(),
+            user_agent_input: String::new(),
             move_confirm_open: false,
             pending_move: None,
             warn_tags_input: Vec::new(),
@@ -91,7 +138,13 @@ impl SettingsUiState {
         self.temp_dir_input = settings.temp_dir.to_string_lossy().to_string();
         self.extract_dir_input = settings.extract_dir.to_string_lossy().to_string();
         self.cache_dir_input = settings.cache_dir.to_string_lossy().to_string();
+        self.archive_library_input = settings.archive_library;
+        self.archive_library_dir_input = settings.archive_library_dir.to_string_lossy().to_string();
+        self.archive_library_max_gb_input = settings.archive_library_max_gb;
         self.custom_launch_input = settings.custom_launch.clone();
+        self.platform_order_input = settings.platform_order.clone();
+        self.hosting_order_input = settings.hosting_order.clone();
+        self.hosting_blacklist_input = settings.hosting_blacklist.clone();
         self.cache_on_download_input = settings.cache_on_download;
         self.language_input = settings.language;
         self.loading_anim_input = settings.loading_anim;
@@ -101,6 +154,22 @@ impl SettingsUiState {
         self.classic_library_toggle_input = settings.classic_library_toggle;
         self.default_bookmark_color_input = settings.default_bookmark_color;
         self.bookmarks_visible_on_cover_input = settings.bookmarks_visible_on_cover;
+        self.extract_threads_input = settings.extract_threads;
+        self.sevenz_memory_limit_input = settings.sevenz_memory_limit_mb;
+        self.archive_name_encoding_input = settings.archive_name_encoding;
+        self.bandwidth_limit_input = settings.bandwidth_limit_kib;
+        self.download_schedule_input = settings.download_schedule;
+        self.download_schedule_start_input = settings.download_schedule_start;
+        self.download_schedule_end_input = settings.download_schedule_end;
+        self.download_connections_input = settings.download_connections;
+        self.site_base_url_input = settings.site_base_url.clone();
+        self.site_attachments_url_input = settings.site_attachments_url.clone();
+        self.site_preview_url_input = settings.site_preview_url.clone();
+        self.site_masked_path_input = settings.site_masked_path.clone();
+        self.proxy_url_input = settings.proxy_url.clone();
+        self.hosting_proxy_separate_input = settings.hosting_proxy_separate;
+        self.hosting_proxy_url_input = settings.hosting_proxy_url.clone();
+        self.user_agent_input = settings.user_agent.clone();
         self.warn_tags_input = settings.warn_tags.clone();
         self.warn_prefixes_input = settings.warn_prefixes.clone();
         self.startup_tags_input = settings.startup_tags.clone();
@@ -125,8 +194,14 @@ impl SettingsUiState {
         settings.startup_prefixes = self.startup_prefixes_input.clone();
         settings.startup_exclude_prefixes = self.startup_exclude_prefixes_input.clone();
         settings.custom_launch = self.custom_launch_input.clone();
+        settings.platform_order = self.platform_order_input.clone();
+        settings.hosting_order = self.hosting_order_input.clone();
+        settings.hosting_blacklist = self.hosting_blacklist_input.clone();
         settings.cache_on_download = self.cache_on_download_input;
         settings.cache_dir = PathBuf::from(&self.cache_dir_input);
+        settings.archive_library = self.archive_library_input;
+        settings.archive_library_dir = PathBuf::from(&self.archive_library_dir_input);
+        settings.archive_library_max_gb = self.archive_library_max_gb_input;
         settings.loading_anim = self.loading_anim_input;
         settings.language = self.language_input;
         settings.log_to_file = self.log_to_file_input;
@@ -135,6 +210,22 @@ impl SettingsUiState {
         settings.classic_library_toggle = self.classic_library_toggle_input;
         settings.default_bookmark_color = self.default_bookmark_color_input;
         settings.bookmarks_visible_on_cover = self.bookmarks_visible_on_cover_input;
+        settings.extract_threads = self.extract_threads_input;
+        settings.sevenz_memory_limit_mb = self.sevenz_memory_limit_input;
+        settings.archive_name_encoding = self.archive_name_encoding_input;
+        settings.bandwidth_limit_kib = self.bandwidth_limit_input;
+        settings.download_schedule = self.download_schedule_input;
+        settings.download_schedule_start = self.download_schedule_start_input;
+        settings.download_schedule_end = self.download_schedule_end_input;
+        settings.download_connections = self.download_connections_input;
+        settings.site_base_url = self.site_base_url_input.trim().to_string();
+        settings.site_attachments_url = self.site_attachments_url_input.trim().to_string();
+        settings.site_preview_url = self.site_preview_url_input.trim().to_string();
+        settings.site_masked_path = self.site_masked_path_input.trim().to_string();
+        settings.proxy_url = self.proxy_url_input.trim().to_string();
+        settings.hosting_proxy_separate = self.hosting_proxy_separate_input;
+        settings.hosting_proxy_url = self.hosting_proxy_url_input.trim().to_string();
+        settings.user_agent = self.user_agent_input.trim().to_string();
     }
 
     fn poll_migration_updates(&mut self) {
@@ -243,6 +334,325 @@ fn render_folder_picker_row(ui: &mut egui::Ui, label: String, value: &mut String
     });
 }
 
+/// Archive library toggle, folder, size limit and current usage.
+fn render_archive_library(ui: &mut egui::Ui, state: &mut SettingsUiState) {
+    ui.checkbox(
+        &mut state.archive_library_input,
+        crate::localization::translate("settings-archive-library"),
+    )
+    .on_hover_text(crate::localization::translate("settings-archive-library-hint"));
+    if !state.archive_library_input {
+        return;
+    }
+    render_folder_picker_row(
+        ui,
+        crate::localization::translate("settings-archive-library-folder"),
+        &mut state.archive_library_dir_input,
+    );
+    ui.horizontal(|ui| {
+        ui.label(crate::localization::translate("settings-archive-library-limit"));
+        let unlimited = crate::localization::translate("settings-sevenz-memory-unlimited");
+        ui.add(
+            egui::DragValue::new(&mut state.archive_library_max_gb_input)
+                .clamp_range(0..=10_000)
+                .custom_formatter(move |v, _| {
+                    if v == 0.0 { unlimited.clone() } else { format!("{v} GiB") }
+                })
+                .custom_parser(|s| s.trim().trim_end_matches("GiB").trim().parse::<f64>().ok()),
+        );
+    });
+    // Usage of the saved library folder; a changed folder applies on Save
+    let (size, count) = crate::game_download::archive_store::usage();
+    ui.horizontal(|ui| {
+        ui.label(crate::localization::translate_with(
+            "settings-archive-library-usage",
+            &[
+                ("count", count.to_string()),
+                ("size", crate::parser::game_info::link::format_size(size)),
+            ],
+        ));
+        if ui
+            .add_enabled(
+                count > 0,
+                egui::Button::new(crate::localization::translate("settings-archive-library-clear")),
+            )
+            .clicked()
+            && let Err(e) = crate::game_download::archive_store::clear()
+        {
+            crate::app::errors_ui::append_error(format!("Archive library: {e}"));
+        }
+    });
+}
+
+/// Global download speed cap, the download window and connections per download.
+fn render_bandwidth(ui: &mut egui::Ui, state: &mut SettingsUiState) {
+    ui.horizontal(|ui| {
+        ui.label(crate::localization::translate("settings-bandwidth-limit"));
+        let unlimited = crate::localization::translate("settings-sevenz-memory-unlimited");
+        ui.add(
+            egui::DragValue::new(&mut state.bandwidth_limit_input)
+                .clamp_range(0..=1_000_000)
+                .speed(64)
+                .custom_formatter(move |v, _| {
+                    if v == 0.0 { unlimited.clone() } else { format!("{v} KiB/s") }
+                })
+                .custom_parser(|s| s.trim().trim_end_matches("KiB/s").trim().parse::<f64>().ok()),
+        )
+        .on_hover_text(crate::localization::translate("settings-bandwidth-limit-hint"));
+    });
+    ui.horizontal(|ui| {
+        ui.checkbox(
+            &mut state.download_schedule_input,
+            crate::localization::translate("settings-download-schedule"),
+        )
+        .on_hover_text(crate::localization::translate("settings-download-schedule-hint"));
+        ui.add_enabled_ui(state.download_schedule_input, |ui| {
+            time_of_day_drag(ui, &mut state.download_schedule_start_input);
+            ui.label("–");
+            time_of_day_drag(ui, &mut state.download_schedule_end_input);
+        });
+    });
+    ui.horizontal(|ui| {
+        ui.label(crate::localization::translate("settings-download-connections"));
+        ui.add(egui::Slider::new(&mut state.download_connections_input, 1..=16))
+            .on_hover_text(crate::localization::translate("settings-download-connections-hint"));
+    });
+}
+
+/// Proxies and user agent.
+fn render_network(ui: &mut egui::Ui, state: &mut SettingsUiState) {
+    ui.label(crate::localization::translate("settings-network"));
+    proxy_row(ui, crate::localization::translate("settings-proxy"), &mut state.proxy_url_input);
+    ui.checkbox(
+        &mut state.hosting_proxy_separate_input,
+        crate::localization::translate("settings-hosting-proxy-separate"),
+    )
+    .on_hover_text(crate::localization::translate("settings-hosting-proxy-separate-hint"));
+    if state.hosting_proxy_separate_input {
+        proxy_row(
+            ui,
+            crate::localization::translate("settings-hosting-proxy"),
+            &mut state.hosting_proxy_url_input,
+        );
+    }
+    ui.horizontal(|ui| {
+        ui.label(crate::localization::translate("settings-user-agent"));
+        ui.add(
+            egui::TextEdit::singleline(&mut state.user_agent_input)
+                .hint_text(crate::net::USER_AGENT)
+                .desired_width(f32::INFINITY),
+        );
+    });
+}
+
+fn proxy_row(ui: &mut egui::Ui, label: String, value: &mut String) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(egui::TextEdit::singleline(value).hint_text("socks5://127.0.0.1:1080"))
+            .on_hover_text(crate::localization::translate("settings-proxy-hint"));
+    });
+    if let Err(e) = crate::net::validate_proxy(value) {
+        ui.colored_label(ui.visuals().error_fg_color, e);
+    }
+}
+
+fn network_inputs_valid(state: &SettingsUiState) -> bool {
+    crate::net::validate_proxy(&state.proxy_url_input).is_ok()
+        && (!state.hosting_proxy_separate_input
+            || crate::net::validate_proxy(&state.hosting_proxy_url_input).is_ok())
+}
+
+/// Site address and CDNs, for mirrors and local test servers. Empty fields use the defaults.
+fn render_site_endpoints(ui: &mut egui::Ui, state: &mut SettingsUiState) {
+    use crate::net::endpoints;
+    egui::CollapsingHeader::new(crate::localization::translate("settings-site-endpoints"))
+        .id_source("settings_site_endpoints")
+        .show(ui, |ui| {
+            if endpoints::env_overridden() {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    crate::localization::translate("settings-site-endpoints-env"),
+                );
+            }
+            let fields = [
+                (
+                    "settings-site-base-url",
+                    &mut state.site_base_url_input,
+                    endpoints::DEFAULT_BASE_URL,
+                ),
+                (
+                    "settings-site-attachments-url",
+                    &mut state.site_attachments_url_input,
+                    endpoints::DEFAULT_ATTACHMENTS_URL,
+                ),
+                (
+                    "settings-site-preview-url",
+                    &mut state.site_preview_url_input,
+                    endpoints::DEFAULT_PREVIEW_URL,
+                ),
+                (
+                    "settings-site-masked-path",
+                    &mut state.site_masked_path_input,
+                    endpoints::DEFAULT_MASKED_PATH,
+                ),
+            ];
+            egui::Grid::new("settings_site_endpoints_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for (key, value, default) in fields {
+                        ui.label(crate::localization::translate(key));
+                        ui.add(egui::TextEdit::singleline(value).hint_text(default));
+                        ui.end_row();
+                    }
+                });
+            ui.label(crate::localization::translate("settings-site-endpoints-hint"));
+        });
+}
+
+/// Minutes after midnight edited as "HH:MM"
+fn time_of_day_drag(ui: &mut egui::Ui, minute: &mut u16) {
+    ui.add(
+        egui::DragValue::new(minute)
+            .clamp_range(0..=(24 * 60 - 1))
+            .speed(5)
+            .custom_formatter(|v, _| crate::game_download::throttle::format_minute(v as u16))
+            .custom_parser(|s| {
+                let (h, m) = s.trim().split_once(':')?;
+                let (h, m) = (h.trim().parse::<u16>().ok()?, m.trim().parse::<u16>().ok()?);
+                (h < 24 && m < 60).then(|| f64::from(h * 60 + m))
+            }),
+    );
+}
+
+/// Ordered platform list with move/remove buttons and a picker for the remaining platforms.
+fn render_platform_order(ui: &mut egui::Ui, order: &mut Vec<DownloadPlatform>) {
+    ui.label(crate::localization::translate("settings-platform-order"));
+    let mut move_up = None;
+    let mut remove = None;
+    for (index, platform) in order.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("{}. {}", index + 1, platform.label()));
+            if ui.add_enabled(index > 0, egui::Button::new("⬆")).clicked() {
+                move_up = Some(index);
+            }
+            if ui.button("×").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = move_up {
+        order.swap(index - 1, index);
+    }
+    if let Some(index) = remove {
+        order.remove(index);
+    }
+
+    let available: Vec<DownloadPlatform> = DownloadPlatform::ALL
+        .into_iter()
+        .filter(|p| !order.contains(p))
+        .collect();
+    ui.horizontal(|ui| {
+        if !available.is_empty() {
+            egui::ComboBox::from_id_source("settings_platform_add_combo")
+                .selected_text(crate::localization::translate("settings-platform-add"))
+                .show_ui(ui, |ui| {
+                    for p in available {
+                        if ui.selectable_label(false, p.label()).clicked() {
+                            order.push(p);
+                        }
+                    }
+                });
+        }
+        if order.is_empty() {
+            ui.label(
+                egui::RichText::new(crate::localization::translate_with(
+                    "settings-platform-order-empty",
+                    &[("platform", DownloadPlatform::host().label().to_string())],
+                ))
+                .small()
+                .weak(),
+            );
+        }
+    });
+}
+
+/// Hosting ranking (tried first, in order), blacklist and per-hosting download statistics.
+fn render_hosting_prefs(ui: &mut egui::Ui, order: &mut Vec<String>, blacklist: &mut Vec<String>) {
+    let all: Vec<String> = crate::parser::game_info::HostingSubset::ALL
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+
+    ui.label(crate::localization::translate("settings-hosting-order"));
+    let mut move_up = None;
+    let mut remove = None;
+    for (index, host) in order.iter().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(format!("{}. {}", index + 1, host));
+            if ui.add_enabled(index > 0, egui::Button::new("⬆")).clicked() {
+                move_up = Some(index);
+            }
+            if ui.button("×").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = move_up {
+        order.swap(index - 1, index);
+    }
+    if let Some(index) = remove {
+        order.remove(index);
+    }
+    let available: Vec<&String> = all.iter().filter(|h| !order.contains(h)).collect();
+    if !available.is_empty() {
+        egui::ComboBox::from_id_source("settings_hosting_add_combo")
+            .selected_text(crate::localization::translate("settings-hosting-add"))
+            .show_ui(ui, |ui| {
+                for host in available {
+                    if ui.selectable_label(false, host).clicked() {
+                        order.push(host.clone());
+                    }
+                }
+            });
+    }
+
+    ui.label(crate::localization::translate("settings-hosting-blacklist"));
+    let stats = super::with_settings(|st| st.hosting_stats.clone());
+    for host in &all {
+        ui.horizontal(|ui| {
+            let mut blocked = blacklist.contains(host);
+            if ui.checkbox(&mut blocked, host).changed() {
+                if blocked {
+                    blacklist.push(host.clone());
+                } else {
+                    blacklist.retain(|h| h != host);
+                }
+            }
+            if let Some(st) = stats.get(host) {
+                let mut text = crate::localization::translate_with(
+                    "settings-hosting-stats",
+                    &[
+                        ("ok", st.successes.to_string()),
+                        ("failed", st.failures.to_string()),
+                    ],
+                );
+                if st.consecutive_failures >= crate::game_download::DEMOTE_AFTER_FAILURES {
+                    text.push_str(" · ");
+                    text.push_str(&crate::localization::translate("settings-hosting-demoted"));
+                }
+                ui.label(egui::RichText::new(text).small().weak());
+            }
+        });
+    }
+    if !stats.is_empty()
+        && ui
+            .button(crate::localization::translate("settings-hosting-reset-stats"))
+            .clicked()
+    {
+        super::store::reset_hosting_stats();
+    }
+}
+
 fn apply_runtime_settings(state: &SettingsUiState) {
     if let Some(lang) = state.language_input {
         let _ = crate::localization::set_current_language(lang);
@@ -250,6 +660,8 @@ fn apply_runtime_settings(state: &SettingsUiState) {
         let _ = crate::localization::set_language_auto();
     }
     crate::logger::set_file_logging_enabled(state.log_to_file_input);
+    // Proxy and user agent changes apply to the next request
+    crate::net::rebuild_clients();
 }
 
 fn apply_settings_and_close(
@@ -320,6 +732,10 @@ fn start_extract_dir_migration(state: &mut SettingsUiState) {
 }
 
 fn handle_save_clicked(ctx: &egui::Context, state: &mut SettingsUiState) {
+    // The network section shows what's wrong with them
+    if !network_inputs_valid(state) {
+        return;
+    }
     let temp_dir = PathBuf::from(&state.temp_dir_input);
     let extract_dir = PathBuf::from(&state.extract_dir_input);
     let (old_extract_dir, has_installed_games) =
@@ -365,6 +781,13 @@ pub fn draw_settings_viewport(ctx: &egui::Context, state: &mut SettingsUiState)
                         crate::localization::translate("settings-cache-folder"),
                         &mut state.cache_dir_input,
                     );
+                    render_archive_library(ui, state);
+                    if ui
+                        .button(crate::localization::translate("settings-import-games-btn"))
+                        .clicked()
+                    {
+                        crate::app::import_ui::open_import();
+                    }
 
                     ui.separator();
 
@@ -553,6 +976,75 @@ pub fn draw_settings_viewport(ctx: &egui::Context, state: &mut SettingsUiState)
                             .hint_text("\"C:\\\\Start.exe\" /box:TestBox {{path}}"),
                     );
 
+                    ui.horizontal(|ui| {
+                        ui.label(crate::localization::translate("settings-extract-threads"));
+                        let auto = crate::localization::translate("settings-extract-threads-auto");
+                        ui.add(
+                            egui::Slider::new(&mut state.extract_threads_input, 0..=16)
+                                .custom_formatter(move |v, _| {
+                                    if v == 0.0 { auto.clone() } else { format!("{v}") }
+                                }),
+                        );
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label(crate::localization::translate("settings-sevenz-memory-limit"));
+                        let unlimited =
+                            crate::localization::translate("settings-sevenz-memory-unlimited");
+                        ui.add(
+                            egui::DragValue::new(&mut state.sevenz_memory_limit_input)
+                                .clamp_range(0..=65536)
+                                .speed(64)
+                                .custom_formatter(move |v, _| {
+                                    if v == 0.0 { unlimited.clone() } else { format!("{v} MiB") }
+                                })
+                                .custom_parser(|s| {
+                                    s.trim().trim_end_matches("MiB").trim().parse::<f64>().ok()
+                                }),
+                        )
+                        .on_hover_text(crate::localization::translate(
+                            "settings-sevenz-memory-limit-hint",
+                        ));
+                    });
+
+                    let encoding_label = |enc: ArchiveNameEncoding| {
+                        enc.label().map(str::to_string).unwrap_or_else(|| {
+                            crate::localization::translate("settings-archive-names-auto")
+                        })
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label(crate::localization::translate("settings-archive-names"));
+                        egui::ComboBox::from_id_source("settings_archive_names_combo")
+                            .selected_text(encoding_label(state.archive_name_encoding_input))
+                            .show_ui(ui, |ui| {
+                                for enc in ArchiveNameEncoding::ALL {
+                                    ui.selectable_value(
+                                        &mut state.archive_name_encoding_input,
+                                        enc,
+                                        encoding_label(enc),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text(crate::localization::translate(
+                                "settings-archive-names-hint",
+                            ));
+                    });
+
+                    render_bandwidth(ui, state);
+
+                    ui.separator();
+
+                    render_platform_order(ui, &mut state.platform_order_input);
+
+                    ui.separator();
+
+                    render_hosting_prefs(
+                        ui,
+                        &mut state.hosting_order_input,
+                        &mut state.hosting_blacklist_input,
+                    );
+
                     ui.separator();
 
                     ui.label(crate::localization::translate("settings-startup-tags"));
@@ -659,6 +1151,11 @@ pub fn draw_settings_viewport(ctx: &egui::Context, state: &mut SettingsUiState)
                         render_prefix_chip_list(ui, &mut state.warn_prefixes_input);
                     });
 
+                    ui.separator();
+
+                    render_network(ui, state);
+                    render_site_endpoints(ui, state);
+
                     ui.add_space(crate::ui_constants::spacing::MEDIUM);
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
                         if ui.button(crate::localization::translate("settings-save")).clicked() {
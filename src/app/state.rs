@@ -6,7 +6,7 @@ use std::sync::mpsc;
 use std::time::Instant;
 
 use super::fetch::CoverMsg;
-use crate::types::{DateLimit, SearchMode, Sorting, TagLogic};
+use crate::types::{DateLimit, LibrarySorting, SearchMode, Sorting, TagLogic};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Screen {
@@ -16,6 +16,7 @@ pub enum Screen {
 
 pub struct FiltersState {
     pub sort: Sorting,
+    pub library_sort: LibrarySorting,
     pub date_limit: DateLimit,
     pub include_logic: TagLogic,
     pub include_tags: Vec<u32>,
@@ -61,6 +62,7 @@ impl Default for FiltersState {
 
         Self {
             sort: Sorting::default(),
+            library_sort: LibrarySorting::default(),
             date_limit: DateLimit::default(),
             include_logic: TagLogic::default(),
             include_tags: inc,
@@ -0,0 +1,299 @@
+// Archive library: with the option on, archives are moved to `<library>/<thread id>/<version>/`
+// after extraction instead of being deleted. The index lives in the same folder, so a library
+// copied to another machine works there too; installs and repairs from it need no network.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use super::integrity::sha256_file;
+use crate::app::settings::with_settings;
+
+/// Index of the stored archives, kept in the library folder
+pub const INDEX_FILE: &str = "index.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredArchive {
+    pub thread_id: u64,
+    pub version: String,
+    pub file_name: String,
+    pub size: u64,
+    pub sha256: String,
+    /// Unix timestamp (seconds); the oldest archives are evicted first
+    pub stored_at: i64,
+}
+
+impl StoredArchive {
+    pub fn path(&self, dir: &Path) -> PathBuf {
+        dir.join(self.thread_id.to_string())
+            .join(version_dir(&self.version))
+            .join(&self.file_name)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ArchiveIndex {
+    archives: Vec<StoredArchive>,
+}
+
+lazy_static! {
+    // Serialises index read-modify-write between background store threads and the UI
+    static ref INDEX_LOCK: Mutex<()> = Mutex::new(());
+}
+
+pub fn enabled() -> bool {
+    with_settings(|st| st.archive_library)
+}
+
+fn library() -> (PathBuf, u64) {
+    with_settings(|st| {
+        (
+            st.archive_library_dir.clone(),
+            u64::from(st.archive_library_max_gb) * 1024 * 1024 * 1024,
+        )
+    })
+}
+
+/// Called once an archive has been extracted: returns it when the library should take it,
+/// otherwise deletes it.
+pub fn keep_or_delete(archive_path: &Path) -> Option<PathBuf> {
+    if enabled() {
+        return Some(archive_path.to_path_buf());
+    }
+    if let Err(e) = fs::remove_file(archive_path) {
+        log::warn!("Failed to delete archive {}: {}", archive_path.display(), e);
+    }
+    None
+}
+
+/// Move an extracted archive into the library in the background.
+pub fn start_store(thread_id: u64, version: String, archive_path: PathBuf) {
+    std::thread::spawn(move || {
+        let (dir, max_bytes) = library();
+        match store_in(&dir, max_bytes, thread_id, &version, &archive_path) {
+            Ok(stored) => log::info!(
+                "Stored {} in the archive library ({} {})",
+                stored.file_name,
+                thread_id,
+                stored.version
+            ),
+            Err(e) => {
+                log::error!("Archive library: {e}");
+                // Don't leave it in temp either
+                let _ = fs::remove_file(&archive_path);
+            }
+        }
+    });
+}
+
+/// Newest stored archive of a thread whose file is still there; with `sha256`, only that exact
+/// archive (a repair must use the archive the game was installed from).
+pub fn find(thread_id: u64, sha256: Option<&str>) -> Option<(StoredArchive, PathBuf)> {
+    let (dir, _) = library();
+    find_in(&dir, thread_id, sha256)
+}
+
+/// Total size of the stored archives and their count.
+pub fn usage() -> (u64, usize) {
+    let (dir, _) = library();
+    let index = read_index(&dir);
+    (
+        index.archives.iter().map(|a| a.size).sum(),
+        index.archives.len(),
+    )
+}
+
+/// Delete every stored archive.
+pub fn clear() -> Result<(), String> {
+    let (dir, _) = library();
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let mut index = read_index(&dir);
+    for archive in index.archives.drain(..) {
+        remove_stored(&dir, &archive);
+    }
+    write_index(&dir, &index)
+}
+
+fn store_in(
+    dir: &Path,
+    max_bytes: u64,
+    thread_id: u64,
+    version: &str,
+    archive_path: &Path,
+) -> Result<StoredArchive, String> {
+    let file_name = archive_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("{} has no file name", archive_path.display()))?;
+    let mut stored = StoredArchive {
+        thread_id,
+        version: version.to_string(),
+        file_name,
+        size: 0,
+        sha256: String::new(),
+        stored_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0),
+    };
+    let target = stored.path(dir);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Create {} failed: {e}", parent.display()))?;
+    }
+    move_file(archive_path, &target)
+        .map_err(|e| format!("Move {} failed: {e}", archive_path.display()))?;
+    stored.size = fs::metadata(&target).map(|m| m.len()).unwrap_or(0);
+    stored.sha256 =
+        sha256_file(&target).map_err(|e| format!("Hash {} failed: {e}", target.display()))?;
+
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let mut index = read_index(dir);
+    index.archives.retain(|a| a.path(dir) != target);
+    index.archives.push(stored.clone());
+    evict_oldest(dir, &mut index, max_bytes);
+    write_index(dir, &index)?;
+    Ok(stored)
+}
+
+fn find_in(dir: &Path, thread_id: u64, sha256: Option<&str>) -> Option<(StoredArchive, PathBuf)> {
+    read_index(dir)
+        .archives
+        .into_iter()
+        .filter(|a| a.thread_id == thread_id)
+        .filter(|a| sha256.is_none_or(|sha| a.sha256 == sha))
+        .map(|a| {
+            let path = a.path(dir);
+            (a, path)
+        })
+        .filter(|(_, path)| path.is_file())
+        .max_by_key(|(a, _)| a.stored_at)
+}
+
+/// Drop the oldest archives until the library fits in `max_bytes` (0 = no limit). The newest
+/// archive is always kept, even when it alone is larger.
+fn evict_oldest(dir: &Path, index: &mut ArchiveIndex, max_bytes: u64) {
+    if max_bytes == 0 {
+        return;
+    }
+    index.archives.sort_by_key(|a| a.stored_at);
+    let mut total: u64 = index.archives.iter().map(|a| a.size).sum();
+    while total > max_bytes && index.archives.len() > 1 {
+        let oldest = index.archives.remove(0);
+        total -= oldest.size;
+        log::info!(
+            "Archive library over its size limit, removing {}",
+            oldest.file_name
+        );
+        remove_stored(dir, &oldest);
+    }
+}
+
+fn remove_stored(dir: &Path, archive: &StoredArchive) {
+    let path = archive.path(dir);
+    if let Err(e) = fs::remove_file(&path) {
+        log::warn!("Failed to remove {}: {}", path.display(), e);
+    }
+    // Version and thread folders go when they become empty
+    for parent in path.ancestors().skip(1).take(2) {
+        if fs::remove_dir(parent).is_err() {
+            break;
+        }
+    }
+}
+
+fn read_index(dir: &Path) -> ArchiveIndex {
+    fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn write_index(dir: &Path, index: &ArchiveIndex) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Create {} failed: {e}", dir.display()))?;
+    let json = serde_json::to_string_pretty(index).map_err(|e| e.to_string())?;
+    fs::write(dir.join(INDEX_FILE), json).map_err(|e| format!("Write index failed: {e}"))
+}
+
+/// Rename, or copy and delete when the library is on another volume.
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
+
+/// Folder name for a version string ("v0.5 Beta" -> "v0.5_Beta").
+fn version_dir(version: &str) -> String {
+    let name: String = version
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.chars().all(|c| c == '.') {
+        "unknown".to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_find_and_evict() {
+        let root = std::env::temp_dir().join(format!("f95-archive-store-{}", uuid::Uuid::new_v4()));
+        let temp = root.join("temp");
+        let library = root.join("library");
+        fs::create_dir_all(&temp).unwrap();
+        let archive = |name: &str, bytes: &[u8]| {
+            let path = temp.join(name);
+            fs::write(&path, bytes).unwrap();
+            path
+        };
+
+        let first = store_in(&library, 0, 7, "v0.1", &archive("Game-0.1.zip", b"0.1")).unwrap();
+        assert!(!temp.join("Game-0.1.zip").exists());
+        assert_eq!(
+            first.path(&library),
+            library.join("7").join("v0.1").join("Game-0.1.zip")
+        );
+        let (found, path) = find_in(&library, 7, Some(&first.sha256)).unwrap();
+        assert_eq!(found.version, "v0.1");
+        assert_eq!(fs::read(path).unwrap(), b"0.1");
+        assert!(find_in(&library, 7, Some("other")).is_none());
+        assert!(find_in(&library, 8, None).is_none());
+
+        // A 4-byte limit only fits the newest archive
+        let second = store_in(
+            &library,
+            0,
+            7,
+            "v0.2 Beta",
+            &archive("Game-0.2.zip", b"0.2"),
+        )
+        .unwrap();
+        let mut index = read_index(&library);
+        // Both were stored within the same second
+        index.archives[1].stored_at = first.stored_at + 1;
+        evict_oldest(&library, &mut index, 4);
+        write_index(&library, &index).unwrap();
+        let (latest, _) = find_in(&library, 7, None).unwrap();
+        assert_eq!(latest.version, "v0.2 Beta");
+        assert!(!library.join("7").join("v0.1").exists());
+        assert!(second.path(&library).ends_with("v0.2_Beta/Game-0.2.zip"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
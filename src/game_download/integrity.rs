@@ -0,0 +1,314 @@
+// Install integrity: after extraction a manifest with the archive's SHA-256 and every extracted
+// file (size + SHA-256) is written into the game folder. "Verify files" compares the folder
+// with it; "Repair" downloads the same archive again and restores only missing/changed files.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Manifest location inside the game folder (not listed in the manifest itself)
+pub const MANIFEST_FILE: &str = ".f95-manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path relative to the game folder, '/'-separated
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub archive_name: String,
+    pub archive_sha256: String,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    pub checked: usize,
+    pub missing: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum VerifyState {
+    Running,
+    Done(VerifyReport),
+    /// The game was installed before manifests existed (or imported)
+    NoManifest,
+    Repairing,
+    Repaired(usize),
+    Failed(String),
+}
+
+lazy_static! {
+    static ref VERIFY_STATES: Mutex<HashMap<u64, VerifyState>> = Mutex::new(HashMap::new());
+}
+
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 256 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hash the archive and every file extracted into `dir`, and save the manifest there.
+pub fn write_manifest(dir: &Path, archive_path: &Path) -> std::io::Result<Manifest> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let manifest = Manifest {
+        archive_name: archive_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        archive_sha256: sha256_file(archive_path)?,
+        files,
+    };
+    let json = serde_json::to_string_pretty(&manifest).map_err(std::io::Error::other)?;
+    fs::write(dir.join(MANIFEST_FILE), json)?;
+    Ok(manifest)
+}
+
+fn collect_files(root: &Path, cur: &Path, out: &mut Vec<ManifestEntry>) -> std::io::Result<()> {
+    for entry in fs::read_dir(cur)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        // A symlink may point outside the game folder
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_files(root, &path, out)?;
+            continue;
+        }
+        let Ok(rel) = path.strip_prefix(root) else {
+            continue;
+        };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        if rel == MANIFEST_FILE {
+            continue;
+        }
+        out.push(ManifestEntry {
+            path: rel,
+            size: fs::metadata(&path)?.len(),
+            sha256: sha256_file(&path)?,
+        });
+    }
+    Ok(())
+}
+
+pub fn read_manifest(dir: &Path) -> Option<Manifest> {
+    let json = fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Compare the folder with its manifest. Files added since install are ignored.
+pub fn verify_folder(dir: &Path, manifest: &Manifest) -> VerifyReport {
+    let mut report = VerifyReport::default();
+    for entry in &manifest.files {
+        report.checked += 1;
+        let path = dir.join(&entry.path);
+        match fs::metadata(&path) {
+            Err(_) => report.missing.push(entry.path.clone()),
+            // Size mismatch is enough, skip hashing
+            Ok(m) if m.len() != entry.size => report.changed.push(entry.path.clone()),
+            Ok(_) => {
+                if sha256_file(&path).ok().as_deref() != Some(entry.sha256.as_str()) {
+                    report.changed.push(entry.path.clone());
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Whether a manifest path stays inside the game folder: relative, without `..`.
+fn is_contained(rel: &str) -> bool {
+    Path::new(rel)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Copy the broken files from a fresh extraction of the same archive into the game folder.
+/// Returns the number of restored files.
+pub fn restore_files(fresh_dir: &Path, game_dir: &Path) -> Result<usize, String> {
+    let installed = read_manifest(game_dir)
+        .ok_or_else(|| format!("No install manifest in {}", game_dir.display()))?;
+    let fresh = read_manifest(fresh_dir)
+        .ok_or_else(|| format!("No manifest in fresh copy {}", fresh_dir.display()))?;
+    if fresh.archive_sha256 != installed.archive_sha256 {
+        return Err(format!(
+            "Downloaded archive {} differs from the installed {} (probably a newer version); \
+             use Update instead",
+            fresh.archive_name, installed.archive_name
+        ));
+    }
+
+    if let Some(entry) = installed.files.iter().find(|e| !is_contained(&e.path)) {
+        return Err(format!(
+            "Install manifest lists a path outside the game folder: {}",
+            entry.path
+        ));
+    }
+
+    let report = verify_folder(game_dir, &installed);
+    let mut restored = 0;
+    for rel in report.missing.iter().chain(report.changed.iter()) {
+        let from = fresh_dir.join(rel);
+        let to = game_dir.join(rel);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Create {} failed: {e}", parent.display()))?;
+        }
+        fs::copy(&from, &to).map_err(|e| format!("Restore {} failed: {e}", to.display()))?;
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+pub fn verify_state(thread_id: u64) -> Option<VerifyState> {
+    VERIFY_STATES.lock().unwrap().get(&thread_id).cloned()
+}
+
+pub fn clear_verify_state(thread_id: u64) {
+    VERIFY_STATES.lock().unwrap().remove(&thread_id);
+}
+
+fn set_state(thread_id: u64, state: VerifyState) {
+    VERIFY_STATES.lock().unwrap().insert(thread_id, state);
+}
+
+/// Verify a game folder in the background; the result is read with `verify_state`.
+pub fn start_verify(thread_id: u64, folder: PathBuf) {
+    set_state(thread_id, VerifyState::Running);
+    std::thread::spawn(move || {
+        let state = match read_manifest(&folder) {
+            Some(manifest) => VerifyState::Done(verify_folder(&folder, &manifest)),
+            None => VerifyState::NoManifest,
+        };
+        set_state(thread_id, state);
+    });
+}
+
+/// Mark a repair download as started so the popup shows its state.
+pub fn mark_repairing(thread_id: u64) {
+    set_state(thread_id, VerifyState::Repairing);
+}
+
+pub fn repair_failed(thread_id: u64, err: String) {
+    set_state(thread_id, VerifyState::Failed(err));
+}
+
+/// Finish a repair: restore from the freshly extracted copy, then delete the copy. The
+/// outcome is read with `verify_state`.
+pub fn start_restore(thread_id: u64, fresh_dir: PathBuf, game_dir: PathBuf) {
+    std::thread::spawn(move || {
+        let result = restore_files(&fresh_dir, &game_dir);
+        if let Err(e) = fs::remove_dir_all(&fresh_dir) {
+            log::warn!(
+                "Failed to remove repair copy {}: {}",
+                fresh_dir.display(),
+                e
+            );
+        }
+        let state = match result {
+            Ok(n) => {
+                log::info!("Repaired {n} file(s) in {}", game_dir.display());
+                VerifyState::Repaired(n)
+            }
+            Err(e) => {
+                log::error!("Repair of {} failed: {e}", game_dir.display());
+                VerifyState::Failed(e)
+            }
+        };
+        set_state(thread_id, state);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_verify_and_restore() {
+        let root = std::env::temp_dir().join(format!("f95-integrity-{}", uuid::Uuid::new_v4()));
+        let game = root.join("game");
+        let fresh = root.join("fresh");
+        for dir in [&game, &fresh] {
+            fs::create_dir_all(dir.join("data")).unwrap();
+            fs::write(dir.join("Game.exe"), b"exe").unwrap();
+            fs::write(dir.join("data/level1.dat"), b"level one").unwrap();
+        }
+        let archive = root.join("Game-1.0.zip");
+        fs::write(&archive, b"archive bytes").unwrap();
+        let manifest = write_manifest(&game, &archive).unwrap();
+        write_manifest(&fresh, &archive).unwrap();
+        assert_eq!(manifest.files.len(), 2);
+        assert!(verify_folder(&game, &manifest).is_ok());
+
+        fs::remove_file(game.join("Game.exe")).unwrap();
+        fs::write(game.join("data/level1.dat"), b"level 0ne").unwrap();
+        let report = verify_folder(&game, &manifest);
+        assert_eq!(report.missing, vec!["Game.exe".to_string()]);
+        assert_eq!(report.changed, vec!["data/level1.dat".to_string()]);
+
+        assert_eq!(restore_files(&fresh, &game).unwrap(), 2);
+        assert!(verify_folder(&game, &manifest).is_ok());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_restore_rejects_paths_outside_the_game_folder() {
+        assert!(is_contained("data/level1.dat"));
+        assert!(!is_contained("../outside.txt"));
+        assert!(!is_contained("data/../../outside.txt"));
+        assert!(!is_contained("/etc/passwd"));
+
+        let root = std::env::temp_dir().join(format!("f95-integrity-{}", uuid::Uuid::new_v4()));
+        let game = root.join("game");
+        let fresh = root.join("fresh");
+        let manifest = Manifest {
+            archive_name: "Game-1.0.zip".to_string(),
+            archive_sha256: "abc".to_string(),
+            files: vec![ManifestEntry {
+                path: "../outside.txt".to_string(),
+                size: 1,
+                sha256: "abc".to_string(),
+            }],
+        };
+        for dir in [&game, &fresh] {
+            fs::create_dir_all(dir).unwrap();
+            let json = serde_json::to_string(&manifest).unwrap();
+            fs::write(dir.join(MANIFEST_FILE), json).unwrap();
+        }
+        fs::write(fresh.join("outside.txt"), b"x").unwrap();
+
+        assert!(restore_files(&fresh, &game).is_err());
+        assert!(!root.join("outside.txt").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+// "Check links": probe every mirror of a thread's Downloads block in parallel, without
+// downloading, so the user sees sizes and dead mirrors before starting a download. Masked links
+// are resolved one at a time, like a download would, to not trip the forum's rate limit.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+use crate::parser::game_info::link::{DownloadLink, LinkProbe};
+use crate::parser::game_info::{F95PageUrl, Platform};
+
+#[derive(Debug, Clone)]
+pub struct PlatformCheck {
+    pub platform: Platform,
+    pub links: Vec<LinkProbe>,
+}
+
+#[derive(Debug, Clone)]
+pub enum LinkCheck {
+    Running,
+    Done(Vec<PlatformCheck>),
+    Failed(String),
+}
+
+lazy_static! {
+    static ref LINK_CHECKS: Mutex<HashMap<u64, LinkCheck>> = Mutex::new(HashMap::new());
+}
+
+/// Start checking all links of a thread; results are read with `link_check`, which the popup
+/// polls while its spinner repaints.
+pub fn start_link_check(thread_id: u64, page: F95PageUrl) {
+    {
+        let mut checks = LINK_CHECKS.lock().unwrap();
+        if matches!(checks.get(&thread_id), Some(LinkCheck::Running)) {
+            return;
+        }
+        checks.insert(thread_id, LinkCheck::Running);
+    }
+
+    crate::app::rt().spawn(async move {
+        let result = match page.get_page().await {
+            Ok(b) => match b.get_download_links() {
+                Ok(downloads) => {
+                    let masked_turn = tokio::sync::Mutex::new(());
+                    let masked_turn = &masked_turn;
+                    let probes = downloads.iter().map(|pd| async move {
+                        let links =
+                            futures::future::join_all(pd.links().iter().map(|l| async move {
+                                let _turn = match l {
+                                    DownloadLink::Masked(_) => Some(masked_turn.lock().await),
+                                    _ => None,
+                                };
+                                l.probe().await
+                            }))
+                            .await;
+                        PlatformCheck {
+                            platform: *pd.platform(),
+                            links,
+                        }
+                    });
+                    LinkCheck::Done(futures::future::join_all(probes).await)
+                }
+                Err(err) => LinkCheck::Failed(err.to_string()),
+            },
+            Err(err) => LinkCheck::Failed(err.to_string()),
+        };
+        if let LinkCheck::Failed(err) = &result {
+            log::warn!("Link check for thread {thread_id} failed: {err}");
+        }
+        LINK_CHECKS.lock().unwrap().insert(thread_id, result);
+    });
+}
+
+pub fn link_check(thread_id: u64) -> Option<LinkCheck> {
+    LINK_CHECKS.lock().unwrap().get(&thread_id).cloned()
+}
+
+pub fn clear_link_check(thread_id: u64) {
+    LINK_CHECKS.lock().unwrap().remove(&thread_id);
+}
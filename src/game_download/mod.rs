@@ -1,12 +1,27 @@
 use std::path::PathBuf;
-use std::sync::mpsc;
+use std::sync::{Arc, mpsc};
+use std::time::Duration;
 
-use crate::parser::game_info::link::DownloadLink;
+use reqwest::Url;
+use tokio::sync::Notify;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::app::settings::store::{DownloadPlatform, record_hosting_result};
+use crate::parser::game_info::link::{DownloadError, DownloadLink};
 use crate::parser::{
     F95Thread,
     game_info::{F95PageUrl, Platform, PlatformDownloads, ThreadId},
 };
 
+pub mod archive_store;
+pub mod integrity;
+pub mod link_check;
+mod ranking;
+pub mod throttle;
+
+pub use ranking::DEMOTE_AFTER_FAILURES;
+use throttle::DownloadThrottle;
+
 #[derive(Debug, Clone)]
 pub enum Progress {
     Pending(f32),
@@ -15,17 +30,264 @@ pub enum Progress {
     Unknown,
 }
 pub enum GameDownloadStatus {
+    // A mirror accepted the request; sent once before its other statuses
+    Started {
+        hosting: String,
+        throttle: Arc<DownloadThrottle>,
+    },
     Downloading(Progress),
+    // Bytes written so far; the download part of the progress bar is derived from it
+    Transferred {
+        done: u64,
+        total: u64,
+    },
     // Signal UI to select a link (no platform parsed)
     SelectLinks(Vec<DownloadLink>),
+    // No preferred platform matched (or the user asked to choose): let the UI pick a build
+    SelectPlatform(Vec<PlatformDownloads>),
+    // A masked link needs its captcha passed in the browser; other mirrors keep being tried
+    CaptchaRequired(CaptchaPrompt),
+    // The hosting broke the transfer (network or HTTP error). Counted against the hosting by
+    // the download task and not forwarded; the error text follows as `Downloading(Error)`
+    HostingFailed,
     Unzipping(Progress),
     Completed {
         dest_dir: PathBuf,
         exe_path: Option<PathBuf>,
+        /// Extracted archive to move into the archive library (None when it was deleted)
+        archive: Option<PathBuf>,
     },
 }
 
-pub fn create_download_task(page: F95PageUrl) -> mpsc::Receiver<GameDownloadStatus> {
+/// How long masked links are retried while waiting for the user to pass the captcha
+const CAPTCHA_WAIT: Duration = Duration::from_secs(10 * 60);
+/// Retry interval when the user doesn't confirm explicitly
+const CAPTCHA_POLL: Duration = Duration::from_secs(30);
+
+/// Masked link waiting for its captcha. `retry` wakes the download task so the masked POST
+/// is repeated right away instead of at the next poll.
+#[derive(Debug, Clone)]
+pub struct CaptchaPrompt {
+    pub url: Url,
+    notify: Arc<Notify>,
+}
+
+impl CaptchaPrompt {
+    pub fn retry(&self) {
+        self.notify.notify_one();
+    }
+}
+
+/// Forward statuses of a started download to the UI and record the hosting's outcome
+/// (success once the transfer reaches extraction). A transfer held by the download window
+/// keeps its connection idle for hours, so its failure isn't blamed on the hosting. Returns
+/// false if the UI went away.
+async fn forward_statuses(
+    mut download_recv: UnboundedReceiver<GameDownloadStatus>,
+    tx: &mpsc::Sender<GameDownloadStatus>,
+    hosting: &str,
+    throttle: &Arc<DownloadThrottle>,
+) -> bool {
+    let mut recorded = false;
+    let mut held = false;
+    let started = GameDownloadStatus::Started {
+        hosting: hosting.to_string(),
+        throttle: throttle.clone(),
+    };
+    if tx.send(started).is_err() {
+        return false;
+    }
+    while let Some(status) = download_recv.recv().await {
+        if !recorded {
+            match &status {
+                GameDownloadStatus::Downloading(Progress::Paused) => held = true,
+                GameDownloadStatus::HostingFailed if held => {
+                    log::info!("{hosting} dropped the transfer after the download window hold");
+                    recorded = true;
+                }
+                GameDownloadStatus::HostingFailed => {
+                    record_hosting_result(hosting, false);
+                    recorded = true;
+                }
+                // Local failures (disk, cancel) aren't the hosting's fault
+                GameDownloadStatus::Downloading(Progress::Error(_)) => recorded = true,
+                GameDownloadStatus::Unzipping(_) | GameDownloadStatus::Completed { .. } => {
+                    record_hosting_result(hosting, true);
+                    recorded = true;
+                }
+                _ => {}
+            }
+        }
+        if matches!(status, GameDownloadStatus::HostingFailed) {
+            continue;
+        }
+        if tx.send(status).is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Retry masked links blocked by a captcha until one resolves, on user confirmation
+/// or every `CAPTCHA_POLL`, giving up after `CAPTCHA_WAIT`. The last prompt is re-sent
+/// before each round so a cancelled download stops retrying.
+async fn retry_captcha_links(
+    links: &[DownloadLink],
+    tx: &mpsc::Sender<GameDownloadStatus>,
+    notify: &Arc<Notify>,
+    throttle: &Arc<DownloadThrottle>,
+) -> Option<(UnboundedReceiver<GameDownloadStatus>, String)> {
+    let prompt = links.last()?;
+    let deadline = tokio::time::Instant::now() + CAPTCHA_WAIT;
+    while tokio::time::Instant::now() < deadline {
+        tokio::select! {
+            _ = notify.notified() => {}
+            _ = tokio::time::sleep(CAPTCHA_POLL) => {}
+        }
+        if !send_captcha_prompt(tx, prompt, notify) {
+            return None; // receiver dropped
+        }
+        for link in links {
+            match link.download(throttle).await {
+                Ok(download_recv) => return Some((download_recv, link.hosting_name())),
+                Err(DownloadError::Captcha) => {}
+                Err(err) => log::warn!("Retry after captcha failed: {err:?}"),
+            }
+        }
+    }
+    None
+}
+
+/// Tell the UI about a captcha-blocked masked link; returns false if the UI went away.
+fn send_captcha_prompt(
+    tx: &mpsc::Sender<GameDownloadStatus>,
+    link: &DownloadLink,
+    notify: &Arc<Notify>,
+) -> bool {
+    let DownloadLink::Masked(url) = link else {
+        return true;
+    };
+    log::warn!("Captcha required for {url}; trying other mirrors meanwhile");
+    tx.send(GameDownloadStatus::CaptchaRequired(CaptchaPrompt {
+        url: url.clone(),
+        notify: notify.clone(),
+    }))
+    .is_ok()
+}
+
+/// Preferred platforms from settings; the running OS when nothing is configured.
+fn preferred_platforms() -> Vec<Platform> {
+    let order = crate::app::settings::with_settings(|st| st.platform_order.clone());
+    if order.is_empty() {
+        vec![DownloadPlatform::host().flag()]
+    } else {
+        order.into_iter().map(DownloadPlatform::flag).collect()
+    }
+}
+
+/// First downloads entry with links matching the platform order.
+fn select_platform<'a>(
+    downloads: &'a [PlatformDownloads],
+    order: &[Platform],
+) -> Option<&'a PlatformDownloads> {
+    order.iter().find_map(|platform| {
+        downloads
+            .iter()
+            .find(|pd| pd.platform().contains(*platform) && !pd.links().is_empty())
+    })
+}
+
+/// Try mirrors in hosting-preference order until one starts, then forward its statuses to the
+/// UI. Captcha-blocked masked links are retried once the other mirrors have failed.
+async fn download_from_links(links: &[DownloadLink], tx: &mpsc::Sender<GameDownloadStatus>) {
+    if !throttle::wait_for_window(tx).await {
+        return;
+    }
+    let ranked = crate::app::settings::with_settings(|st| {
+        ranking::rank_links(
+            links,
+            &st.hosting_order,
+            &st.hosting_blacklist,
+            &st.hosting_stats,
+        )
+    });
+    if ranked.is_empty() && !links.is_empty() {
+        let _ = tx.send(GameDownloadStatus::Downloading(Progress::Error(
+            "All mirrors of this build are on the hosting blacklist".to_string(),
+        )));
+        return;
+    }
+
+    let mut errors = vec![];
+    let mut captcha_links = vec![];
+    let notify = Arc::new(Notify::new());
+    let throttle = DownloadThrottle::new();
+    for link in &ranked {
+        match link.download(&throttle).await {
+            Ok(download_recv) => {
+                forward_statuses(download_recv, tx, &link.hosting_name(), &throttle).await;
+                return;
+            }
+            Err(DownloadError::Captcha) => {
+                if !send_captcha_prompt(tx, link, &notify) {
+                    return; // Получатель отключился
+                }
+                errors.push(format!("{:?}", DownloadError::Captcha));
+                captcha_links.push(link.clone());
+            }
+            Err(DownloadError::NotEnoughSpace(msg)) => {
+                // Every mirror serves the same file, the others would fail the same way
+                log::error!("{msg}");
+                let _ = tx.send(GameDownloadStatus::Downloading(Progress::Error(msg)));
+                return;
+            }
+            Err(err) => {
+                log::error!("Error downloading: {err:?}");
+                if err.is_hosting_failure() {
+                    record_hosting_result(&link.hosting_name(), false);
+                }
+                let err = format!("{err:?}");
+                errors.push(err);
+            }
+        }
+    }
+
+    // Other mirrors failed: wait for the user to pass a captcha
+    if !captcha_links.is_empty()
+        && let Some((download_recv, hosting)) =
+            retry_captcha_links(&captcha_links, tx, &notify, &throttle).await
+    {
+        forward_statuses(download_recv, tx, &hosting, &throttle).await;
+        return;
+    }
+
+    // Если ни одна ссылка не сработала
+    let error_text = if errors.len() == 0 {
+        "For some reason no download links was found".to_string()
+    } else {
+        format!("Errors trying download from hostings: {errors:?}")
+    };
+    let _ = tx.send(GameDownloadStatus::Downloading(Progress::Error(error_text)));
+}
+
+/// Download a build the user picked from the platform prompt.
+pub fn create_download_from_links(links: Vec<DownloadLink>) -> mpsc::Receiver<GameDownloadStatus> {
+    let rt = crate::app::RUNTIME.get().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    rt.spawn(async move {
+        download_from_links(&links, &tx).await;
+    });
+
+    rx
+}
+
+/// Download the thread's build for the preferred platform. With `choose_platform` (or when
+/// none of the preferred platforms is offered) the UI is asked to pick a platform instead.
+pub fn create_download_task(
+    page: F95PageUrl,
+    choose_platform: bool,
+) -> mpsc::Receiver<GameDownloadStatus> {
     let rt = crate::app::RUNTIME.get().unwrap();
 
     // Создаем канал для передачи статусов загрузки
@@ -61,62 +323,33 @@ pub fn create_download_task(page: F95PageUrl) -> mpsc::Receiver<GameDownloadStat
         };
         dbg!(&downloads);
 
-        // Auto-select platform based on host OS; fall back to any available platform with links
-        let preferred_platform = if cfg!(target_os = "windows") {
-            Platform::WINDOWS
-        } else if cfg!(target_os = "linux") {
-            Platform::LINUX
-        } else if cfg!(target_os = "macos") {
-            Platform::MAC
-        } else if cfg!(target_os = "android") {
-            Platform::ANDROID
+        let order = preferred_platforms();
+        let selected = if choose_platform {
+            None
         } else {
-            Platform::WINDOWS
+            select_platform(&downloads, &order)
         };
 
-        // Try preferred platform first, then common priority, then any with most links
-        let selected = downloads
-            .iter()
-            .find(|e| e.platform().contains(preferred_platform));
-
         let links = match selected {
-            Some(pd) if !pd.links().is_empty() => pd.links(),
-            _ => {
-                let message = format!(
-                    "No suitable platform downloads found. Available: {:?}",
+            Some(pd) => pd.links().clone(),
+            None if downloads.iter().all(|pd| pd.links().is_empty()) => {
+                let _ = tx.send(GameDownloadStatus::Downloading(Progress::Error(
+                    "For some reason no download links was found".to_string(),
+                )));
+                return;
+            }
+            None => {
+                // Let the user pick any of the available builds instead of failing
+                log::info!(
+                    "No preferred platform ({order:?}) among {:?}; asking the user",
                     downloads.iter().map(|e| e.platform()).collect::<Vec<_>>()
                 );
-                let _ = tx.send(GameDownloadStatus::Downloading(Progress::Error(message)));
+                let _ = tx.send(GameDownloadStatus::SelectPlatform(downloads));
                 return;
             }
         };
 
-        let mut errors = vec![];
-        for link in links {
-            match link.download().await {
-                Ok(mut download_recv) => {
-                    while let Some(status) = download_recv.recv().await {
-                        if tx.send(status).is_err() {
-                            return; // Получатель отключился
-                        }
-                    }
-                    return;
-                }
-                Err(err) => {
-                    log::error!("Error downloading: {err:?}");
-                    let err = format!("{err:?}");
-                    errors.push(err);
-                }
-            }
-        }
-
-        // Если ни одна ссылка не сработала
-        let error_text = if errors.len() == 0 {
-            "For some reason no download links was found".to_string()
-        } else {
-            format!("Errors trying download from hostings: {errors:?}")
-        };
-        let _ = tx.send(GameDownloadStatus::Downloading(Progress::Error(error_text)));
+        download_from_links(&links, &tx).await;
     });
 
     rx
@@ -127,13 +360,26 @@ pub fn create_download_from_link(link: DownloadLink) -> mpsc::Receiver<GameDownl
     let (tx, rx) = mpsc::channel();
 
     rt.spawn(async move {
-        match link.download().await {
-            Ok(mut download_recv) => {
-                while let Some(status) = download_recv.recv().await {
-                    if tx.send(status).is_err() {
-                        return; // receiver dropped
-                    }
+        if !throttle::wait_for_window(&tx).await {
+            return;
+        }
+        let throttle = DownloadThrottle::new();
+        let result = match link.download(&throttle).await {
+            Err(DownloadError::Captcha) => {
+                let notify = Arc::new(Notify::new());
+                if !send_captcha_prompt(&tx, &link, &notify) {
+                    return; // receiver dropped
                 }
+                retry_captcha_links(std::slice::from_ref(&link), &tx, &notify, &throttle)
+                    .await
+                    .map(|(download_recv, _)| download_recv)
+                    .ok_or(DownloadError::Captcha)
+            }
+            other => other,
+        };
+        match result {
+            Ok(download_recv) => {
+                forward_statuses(download_recv, &tx, &link.hosting_name(), &throttle).await;
             }
             Err(err) => {
                 let _ = tx.send(GameDownloadStatus::Downloading(Progress::Error(format!(
@@ -145,3 +391,94 @@ pub fn create_download_from_link(link: DownloadLink) -> mpsc::Receiver<GameDownl
 
     rx
 }
+
+/// Install a game from an archive the user already has on disk (e.g. downloaded in the browser).
+/// Runs the normal extraction into the Extract-to folder and reports through the same statuses
+/// as a download, so the card shows unzip progress and the game is recorded on completion.
+pub fn create_install_from_file(
+    archive_path: PathBuf,
+    keep_archive: bool,
+) -> mpsc::Receiver<GameDownloadStatus> {
+    let rt = crate::app::RUNTIME.get().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    rt.spawn(async move {
+        let dest_base = crate::app::settings::APP_SETTINGS
+            .read()
+            .unwrap()
+            .extract_dir
+            .clone();
+        let (sd, mut unzip_rx) = tokio::sync::mpsc::unbounded_channel();
+        let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(0.0)));
+        log::info!("installing from local archive {}", archive_path.display());
+
+        let path = archive_path.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            crate::parser::game_info::link::extract_archive(&path, &dest_base, &sd)
+        });
+        while let Some(status) = unzip_rx.recv().await {
+            if tx.send(status).is_err() {
+                return; // receiver dropped
+            }
+        }
+
+        let status = match task.await {
+            Ok(Ok((dest_dir, exe_path))) => {
+                let archive = if keep_archive {
+                    None
+                } else {
+                    archive_store::keep_or_delete(&archive_path)
+                };
+                GameDownloadStatus::Completed {
+                    dest_dir,
+                    exe_path,
+                    archive,
+                }
+            }
+            Ok(Err(msg)) => GameDownloadStatus::Unzipping(Progress::Error(msg)),
+            Err(e) => GameDownloadStatus::Unzipping(Progress::Error(format!(
+                "Unzip task join error: {e}"
+            ))),
+        };
+        let _ = tx.send(status);
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(platform: Platform, urls: &[&str]) -> PlatformDownloads {
+        let links = urls
+            .iter()
+            .filter_map(|u| DownloadLink::new(Url::parse(u).unwrap()))
+            .collect();
+        PlatformDownloads::new(platform, links)
+    }
+
+    #[test]
+    fn test_select_platform_follows_order() {
+        let downloads = vec![
+            entry(
+                Platform::WINDOWS | Platform::LINUX,
+                &["https://pixeldrain.com/u/win"],
+            ),
+            entry(Platform::ANDROID, &["https://pixeldrain.com/u/apk"]),
+            entry(Platform::MAC, &[]),
+        ];
+
+        let picked = select_platform(&downloads, &[Platform::ANDROID, Platform::WINDOWS]);
+        assert_eq!(picked.map(|pd| *pd.platform()), Some(Platform::ANDROID));
+
+        let picked = select_platform(&downloads, &[Platform::LINUX]);
+        assert_eq!(
+            picked.map(|pd| *pd.platform()),
+            Some(Platform::WINDOWS | Platform::LINUX)
+        );
+
+        // Entries without links are skipped
+        assert!(select_platform(&downloads, &[Platform::MAC]).is_none());
+    }
+}
@@ -0,0 +1,110 @@
+// Mirror ordering: apply the user's hosting ranking and blacklist to a platform's links and
+// push hostings that keep failing to the back until they've had time to recover.
+
+use std::collections::HashMap;
+
+use crate::app::settings::store::HostingStats;
+use crate::parser::game_info::link::DownloadLink;
+
+/// Consecutive failures after which a hosting is tried after all others
+pub const DEMOTE_AFTER_FAILURES: u32 = 3;
+/// Seconds after the last failure when a demoted hosting gets its place back
+pub const DEMOTE_COOLDOWN_SECS: i64 = 24 * 60 * 60;
+
+/// Whether `stats` put the hosting at the back at `now` (Unix seconds)
+fn is_demoted(stats: &HostingStats, now: i64) -> bool {
+    stats.consecutive_failures >= DEMOTE_AFTER_FAILURES
+        && stats
+            .last_failure
+            .is_some_and(|at| now - at < DEMOTE_COOLDOWN_SECS)
+}
+
+/// Reorder mirrors: ranked hostings first (in ranking order), the rest in page order,
+/// demoted hostings last. Blacklisted hostings are dropped.
+pub fn rank_links(
+    links: &[DownloadLink],
+    order: &[String],
+    blacklist: &[String],
+    stats: &HashMap<String, HostingStats>,
+) -> Vec<DownloadLink> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let mut keyed: Vec<(bool, usize, usize, &DownloadLink)> = links
+        .iter()
+        .enumerate()
+        .filter_map(|(page_idx, link)| {
+            let host = link.hosting_name();
+            if blacklist.contains(&host) {
+                return None;
+            }
+            let demoted = stats.get(&host).is_some_and(|s| is_demoted(s, now));
+            let rank = order.iter().position(|h| *h == host).unwrap_or(order.len());
+            Some((demoted, rank, page_idx, link))
+        })
+        .collect();
+    keyed.sort_by_key(|(demoted, rank, page_idx, _)| (*demoted, *rank, *page_idx));
+    keyed.into_iter().map(|(.., link)| link.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(urls: &[&str]) -> Vec<DownloadLink> {
+        urls.iter()
+            .map(|u| DownloadLink::from_user_input(u).unwrap())
+            .collect()
+    }
+
+    fn hosts(links: &[DownloadLink]) -> Vec<String> {
+        links.iter().map(|l| l.hosting_name()).collect()
+    }
+
+    #[test]
+    fn test_rank_links_order_blacklist_and_demotion() {
+        let page = links(&[
+            "https://mega.nz/file/abc#key",
+            "https://gofile.io/d/abc",
+            "https://pixeldrain.com/u/abc",
+            "https://files.catbox.moe/abc.zip",
+        ]);
+        let order = vec!["pixeldrain.com".to_string()];
+        let blacklist = vec!["gofile.io".to_string()];
+        let mut stats = HashMap::new();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let ranked = rank_links(&page, &order, &blacklist, &stats);
+        assert_eq!(
+            hosts(&ranked),
+            vec!["pixeldrain.com", "mega.nz", "files.catbox.moe"]
+        );
+
+        stats.insert(
+            "mega.nz".to_string(),
+            HostingStats {
+                failures: 5,
+                consecutive_failures: DEMOTE_AFTER_FAILURES,
+                last_failure: Some(now),
+                ..Default::default()
+            },
+        );
+        let ranked = rank_links(&page, &order, &blacklist, &stats);
+        assert_eq!(
+            hosts(&ranked),
+            vec!["pixeldrain.com", "files.catbox.moe", "mega.nz"]
+        );
+
+        // The demotion expires after the cooldown
+        stats.get_mut("mega.nz").unwrap().last_failure = Some(now - DEMOTE_COOLDOWN_SECS);
+        let ranked = rank_links(&page, &order, &blacklist, &stats);
+        assert_eq!(
+            hosts(&ranked),
+            vec!["pixeldrain.com", "mega.nz", "files.catbox.moe"]
+        );
+    }
+}
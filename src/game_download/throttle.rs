@@ -0,0 +1,200 @@
+// Download speed caps and the download window. Every transfer paces its writes against the
+// global cap from settings and its own cap (set from the downloads window); a transfer waits for
+// whichever is slower. New downloads outside the scheduled window wait until it opens, and
+// running ones pause when it closes.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
+
+use chrono::Timelike;
+use lazy_static::lazy_static;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{GameDownloadStatus, Progress};
+use crate::app::settings::with_settings;
+
+/// How often a download held by the window checks the clock again
+const WINDOW_POLL: Duration = Duration::from_secs(30);
+
+/// Paces writes to a byte rate: each write pushes the time the next one may start.
+#[derive(Default)]
+struct Pace {
+    next: Mutex<Option<Instant>>,
+}
+
+impl Pace {
+    /// Book `bytes` at `bytes_per_sec`; returns how long the caller has to wait.
+    fn reserve(&self, bytes: u64, bytes_per_sec: u64, now: Instant) -> Duration {
+        let cost = Duration::from_secs_f64(bytes as f64 / bytes_per_sec as f64);
+        let mut next = self.next.lock().unwrap();
+        // Idle time isn't saved up: a cap raised or a pause doesn't allow a burst afterwards
+        let start = next.filter(|n| *n > now).unwrap_or(now);
+        *next = Some(start + cost);
+        (start + cost).saturating_duration_since(now)
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL_PACE: Pace = Pace::default();
+}
+
+/// Speed cap of one download. Shared between the transfer and the UI.
+#[derive(Default)]
+pub struct DownloadThrottle {
+    /// KiB/s, 0 = only the global cap applies
+    limit_kib: AtomicU32,
+    pace: Pace,
+}
+
+impl DownloadThrottle {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn limit_kib(&self) -> u32 {
+        self.limit_kib.load(Ordering::Relaxed)
+    }
+
+    pub fn set_limit_kib(&self, kib: u32) {
+        self.limit_kib.store(kib, Ordering::Relaxed);
+    }
+
+    /// Account for `bytes` just written; returns how long to wait before writing more.
+    pub fn reserve(&self, bytes: u64) -> Duration {
+        let now = Instant::now();
+        let global_kib = with_settings(|st| st.bandwidth_limit_kib);
+        let mut wait = Duration::ZERO;
+        if global_kib > 0 {
+            wait = GLOBAL_PACE.reserve(bytes, u64::from(global_kib) * 1024, now);
+        }
+        let own_kib = self.limit_kib();
+        if own_kib > 0 {
+            wait = wait.max(self.pace.reserve(bytes, u64::from(own_kib) * 1024, now));
+        }
+        wait
+    }
+
+    /// Async form of [`reserve`](Self::reserve) for streaming loops; also pauses while the
+    /// download window is closed (see [`hold_while_closed`](Self::hold_while_closed)).
+    pub async fn consume(&self, bytes: u64, sd: &UnboundedSender<GameDownloadStatus>) {
+        let wait = self.reserve(bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        self.hold_while_closed(sd).await;
+    }
+
+    /// Wait until the download window is open, sending `Paused` to `sd` on every poll: the UI
+    /// shows the transfer as scheduled, and once the download is cancelled the send fails and
+    /// the wait ends early, so the transfer's next status send stops it.
+    pub async fn hold_while_closed(&self, sd: &UnboundedSender<GameDownloadStatus>) {
+        if window_open() {
+            return;
+        }
+        log::info!(
+            "Download window {} closed, pausing transfer",
+            window_label()
+        );
+        while !window_open() {
+            if sd
+                .send(GameDownloadStatus::Downloading(Progress::Paused))
+                .is_err()
+            {
+                return;
+            }
+            tokio::time::sleep(WINDOW_POLL).await;
+        }
+        log::info!("Download window open, resuming transfer");
+    }
+}
+
+/// Whether `minute` (after local midnight) is inside the window. The window may wrap past
+/// midnight (23:00-07:00); equal ends mean all day.
+fn in_window(minute: u32, start: u32, end: u32) -> bool {
+    if start == end {
+        true
+    } else if start < end {
+        (start..end).contains(&minute)
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// Whether downloads may start or run now.
+pub fn window_open() -> bool {
+    let (enabled, start, end) = with_settings(|st| {
+        (
+            st.download_schedule,
+            u32::from(st.download_schedule_start),
+            u32::from(st.download_schedule_end),
+        )
+    });
+    if !enabled {
+        return true;
+    }
+    let now = chrono::Local::now();
+    in_window(now.hour() * 60 + now.minute(), start, end)
+}
+
+/// "01:00-07:00"
+pub fn window_label() -> String {
+    with_settings(|st| {
+        format!(
+            "{}-{}",
+            format_minute(st.download_schedule_start),
+            format_minute(st.download_schedule_end)
+        )
+    })
+}
+
+pub fn format_minute(minute: u16) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+/// Hold a new download until the window opens, showing it as paused meanwhile. Returns false
+/// if the UI went away while waiting.
+pub(super) async fn wait_for_window(tx: &mpsc::Sender<GameDownloadStatus>) -> bool {
+    let mut logged = false;
+    while !window_open() {
+        if !logged {
+            log::info!("Outside the download window {}, waiting", window_label());
+            logged = true;
+        }
+        if tx
+            .send(GameDownloadStatus::Downloading(Progress::Paused))
+            .is_err()
+        {
+            return false;
+        }
+        tokio::time::sleep(WINDOW_POLL).await;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pace_and_window() {
+        let pace = Pace::default();
+        let now = Instant::now();
+        // 1000 B/s: the first 500 bytes take half a second, the next ones queue behind them
+        assert_eq!(pace.reserve(500, 1000, now), Duration::from_millis(500));
+        assert_eq!(pace.reserve(500, 1000, now), Duration::from_secs(1));
+        // After an idle period nothing is owed
+        let later = now + Duration::from_secs(5);
+        assert_eq!(pace.reserve(1000, 1000, later), Duration::from_secs(1));
+
+        assert!(in_window(90, 60, 420));
+        assert!(!in_window(420, 60, 420));
+        assert!(!in_window(30, 60, 420));
+        // 23:00-07:00 wraps past midnight
+        assert!(in_window(23 * 60 + 30, 23 * 60, 420));
+        assert!(in_window(60, 23 * 60, 420));
+        assert!(!in_window(12 * 60, 23 * 60, 420));
+        assert!(in_window(12 * 60, 300, 300));
+        assert_eq!(format_minute(65), "01:05");
+    }
+}
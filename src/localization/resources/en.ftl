@@ -4,6 +4,7 @@ settings-window-title = Settings
 settings-temp-folder = Temp folder:
 settings-extract-folder = Extract-to folder:
 settings-cache-folder = Cache folder:
+settings-import-games-btn = Import existing games...
 settings-language = Language:
 settings-language-auto = Auto (System)
 settings-language-en = English
@@ -70,6 +71,10 @@ sorting-likes = Likes
 sorting-views = Views
 sorting-title = Title
 sorting-rating = Rating
+library-sorting-added = Added
+library-sorting-title = Title
+library-sorting-playtime = Playtime
+library-sorting-last-played = Last played
 
 view-mode-header = MODE
 view-mode-catalog = Catalog
@@ -94,6 +99,7 @@ search-mode-title = TITLE
 # Common buttons
 common-logs = Logs
 common-about = About
+common-downloads = Downloads
 common-settings = Settings
 common-bookmarks = Bookmarks
 
@@ -136,6 +142,12 @@ settings-update-on-startup = On startup
 settings-update-every-n-days = Every { $days } days
 settings-checking-updates = Checking updates...
 card-update-available = UPDATE
+card-last-played-today = Last played today
+card-last-played-days-ago = Last played { $days } days ago
+card-running = RUNNING
+card-running-for = Running for { $minutes } min
+card-stop-game = ⏹ Stop game
+card-already-running = Already running
 
 card-context-bookmarks = 🔖 Bookmarks...
 
@@ -157,3 +169,183 @@ bookmarks-mgmt-default-color = Default bookmark color:
 bookmarks-mgmt-visible-limit = Bookmarks visible on cover:
 bookmarks-mgmt-no-bookmarks = No bookmarks created yet.
 
+# Launch profiles
+card-context-launch-profile = ▶ Launch profile
+launch-profiles-default = Default
+launch-profiles-edit = Edit profiles...
+launch-profiles-title = Launch Profiles
+launch-profiles-name = Profile name
+launch-profiles-exe = Executable
+launch-profiles-args = Arguments
+launch-profiles-wrapper = Wrapper
+launch-profiles-workdir = Working dir
+launch-profiles-env = Environment
+launch-profiles-placeholders = Placeholders: {"{{"}path{"}}"}, {"{{"}dir{"}}"}, {"{{"}title{"}}"}, {"{{"}thread_id{"}}"}. Quote arguments with spaces.
+launch-profiles-add = + Add profile
+launch-profiles-save = Save
+
+# Linux sandbox
+card-context-sandbox = 🛡 Sandbox (no network)
+card-context-sandbox-network = Allow network
+
+# Import existing game folders
+import-window-title = Import existing games
+import-choose-folder = Choose folder...
+import-searching = Searching F95...
+import-no-matches = No matching threads found
+import-nothing-found = No new game folders found in this folder
+import-skip = Skip
+import-confirm = Import selected ({ $count })
+
+# Install from a local archive
+card-context-install-from-file = 📦 Install from file...
+card-context-keep-archive = Keep archive after install
+
+# Manual download URL
+card-context-download-url = 🔗 Download from URL...
+download-url-title = Download from URL
+download-url-hint = Paste a hosting link (pixeldrain, gofile, catbox, MEGA, F95 masked) or a direct file URL
+download-url-supported = Supported hosting link
+download-url-plain-http = Will be downloaded as a plain file
+download-url-invalid = Not a valid http(s) URL
+download-url-unsupported-hosting = This masked link points to an unsupported hosting
+download-url-start = Download
+
+# Captcha on masked links
+card-captcha-text = A mirror needs a captcha. Open it in the browser, pass the captcha, then press Retry. Other mirrors are tried meanwhile.
+card-captcha-open = Open in browser
+card-captcha-retry = I passed it, retry
+card-captcha = CAPTCHA
+
+# Download platform preference
+settings-platform-order = Download platforms (first available wins):
+settings-platform-add = Add platform...
+settings-platform-order-empty = Empty: only { $platform } (this OS)
+card-context-choose-platform = ⬇ Download (choose platform)...
+card-select-platform = SELECT PLATFORM
+
+# Hosting preference
+settings-hosting-order = Preferred hostings (tried first, in this order):
+settings-hosting-add = Add hosting...
+settings-hosting-blacklist = Never download from:
+settings-hosting-stats = { $ok } ok / { $failed } failed
+settings-hosting-demoted = tried last (keeps failing)
+settings-hosting-reset-stats = Reset hosting statistics
+
+# Link check
+card-context-check-links = 🔍 Check links...
+link-check-title = Download mirrors
+link-check-running = Checking links...
+link-check-no-links = No download links found in the thread
+link-check-reachable = OK
+link-check-captcha = Captcha
+link-check-dead = Dead
+link-check-unknown-size = size unknown
+link-check-close = Close
+
+# Verify files
+card-context-verify-files = ✔ Verify files...
+verify-title = Installed files
+verify-running = Checking files...
+verify-no-manifest = No install record for this game (installed by an older version or imported), nothing to compare with.
+verify-ok = All { $count } files are intact
+verify-missing = Missing files: { $count }
+verify-changed = Changed files: { $count }
+verify-repair = Repair
+verify-repair-hint = Download the archive again and restore only the missing and changed files
+verify-repairing = Repairing: downloading the archive...
+verify-repaired = Repaired { $count } file(s)
+
+# Extraction
+settings-extract-threads = Zip extraction threads:
+settings-extract-threads-auto = auto
+
+# 7z memory limit
+settings-sevenz-memory-limit = 7z memory limit:
+settings-sevenz-memory-unlimited = no limit
+settings-sevenz-memory-limit-hint = 7z archives whose decompression dictionary needs more memory than this are refused before extraction instead of running out of memory. 0 = no limit.
+
+# Archive name encoding
+settings-archive-names = Archive file name encoding:
+settings-archive-names-auto = Auto-detect
+settings-archive-names-hint = Used for file names inside archives that aren't stored as UTF-8 (Japanese, Chinese or Russian games packed with older tools). Pick the code page if auto-detection produces garbled names, then extract again.
+
+# Archive library
+settings-archive-library = Keep downloaded archives (archive library)
+settings-archive-library-hint = After extraction archives are moved into the library folder, by thread id and version, instead of being deleted. Games can then be reinstalled or repaired from it without downloading. The folder can be copied to other machines.
+settings-archive-library-folder = Archive library folder:
+settings-archive-library-limit = Library size limit:
+settings-archive-library-usage = { $count } archive(s), { $size }
+settings-archive-library-clear = Delete all stored archives
+card-context-install-from-library = Install from archive library ({ $version })
+
+# Downloads window
+downloads-window-title = Downloads
+downloads-active = Active ({ $count })
+downloads-queued = Interrupted ({ $count })
+downloads-history = History ({ $count })
+downloads-none = Nothing here
+downloads-col-title = Game
+downloads-col-phase = Status
+downloads-col-bytes = Downloaded
+downloads-col-speed = Speed
+downloads-col-eta = Time left
+downloads-col-hosting = Hosting
+downloads-col-size = Size
+downloads-col-duration = Took
+downloads-col-checksum = SHA-256
+downloads-col-finished = Finished
+downloads-phase-starting = Starting
+downloads-phase-downloading = Downloading
+downloads-phase-extracting = Extracting
+downloads-phase-choose = Waiting for a choice on the card
+downloads-phase-captcha = Waiting for captcha
+downloads-phase-failed = Failed
+downloads-retry = Retry
+downloads-dismiss = Dismiss
+downloads-cancel = Cancel
+downloads-cancel-extracting = Extraction can't be cancelled
+downloads-cancelled = Cancelled
+downloads-captcha-retry = Captcha passed, retry
+downloads-start = Start
+downloads-remove = Remove
+downloads-open-folder = Open folder
+downloads-clear-history = Clear history
+downloads-local-file = local file
+downloads-copy-checksum = { $sha } (click to copy)
+downloads-finished-today = today
+downloads-finished-days-ago = { $days } d ago
+
+# Bandwidth limit and download window
+settings-bandwidth-limit = Download speed limit:
+settings-bandwidth-limit-hint = Cap for all downloads together. Single downloads can get their own cap in the Downloads window. 0 = no limit.
+settings-download-schedule = Only download between
+settings-download-schedule-hint = Downloads started outside this window wait until it opens, and running ones pause when it closes (local time). The window may pass midnight, e.g. 23:00–07:00.
+downloads-col-limit = Speed limit
+downloads-limit-none = no limit
+downloads-limit-hint = Speed cap for this download only; the global limit from Settings still applies
+downloads-phase-scheduled = Waiting for the download window
+card-scheduled-text = Waiting for the download window ({ $window })
+card-scheduled = SCHEDULED
+
+# Segmented downloads
+settings-download-connections = Connections per download:
+settings-download-connections-hint = Download large files over several connections at once when the server supports it (pixeldrain, catbox and other hosts that limit each connection). 1 = one connection. Other servers always use one.
+
+# Site endpoints
+settings-site-endpoints = Site address (advanced)
+settings-site-base-url = Forum:
+settings-site-attachments-url = Attachments CDN:
+settings-site-preview-url = Preview CDN:
+settings-site-masked-path = Masked link path:
+settings-site-endpoints-hint = Empty fields use the default f95zone address. Change these only for a mirror domain or a local test server; applies after Save.
+settings-site-endpoints-env = Set by environment variables (F95_BASE_URL and others); those take precedence over these fields.
+
+# Network
+settings-network = Network
+settings-proxy = Proxy:
+settings-proxy-hint = http://, https://, socks5:// or socks5h:// (DNS through the proxy), optionally with user:password@. Empty = direct connection.
+settings-hosting-proxy-separate = Use a different proxy for file hostings
+settings-hosting-proxy-separate-hint = Downloads from file hostings (Pixeldrain, Gofile, MEGA and others) go through the proxy below instead; leave it empty to download them directly.
+settings-hosting-proxy = File hostings proxy:
+settings-user-agent = User agent:
@@ -4,6 +4,7 @@ settings-window-title = Настройки
 settings-temp-folder = Временная папка:
 settings-extract-folder = Папка для распаковки:
 settings-cache-folder = Папка кэша:
+settings-import-games-btn = Импортировать установленные игры...
 settings-language = Язык:
 settings-language-auto = Авто (система)
 settings-language-en = Английский
@@ -70,6 +71,10 @@ sorting-likes = Лайки
 sorting-views = Просмотры
 sorting-title = Название
 sorting-rating = Рейтинг
+library-sorting-added = Добавлено
+library-sorting-title = Название
+library-sorting-playtime = Время игры
+library-sorting-last-played = Последний запуск
 
 view-mode-header = РЕЖИМ
 view-mode-catalog = Каталог
@@ -94,6 +99,7 @@ search-mode-title = НАЗВАНИЕ
 # Общие кнопки
 common-logs = Логи
 common-about = О программе
+common-downloads = Загрузки
 common-settings = Настройки
 common-bookmarks = Закладки
 
@@ -136,6 +142,12 @@ settings-update-on-startup = При запуске
 settings-update-every-n-days = Каждые { $days } дней
 settings-checking-updates = Проверка обновлений...
 card-update-available = ОБНОВИТЬ
+card-last-played-today = Последний запуск сегодня
+card-last-played-days-ago = Последний запуск { $days } дн. назад
+card-running = ЗАПУЩЕНА
+card-running-for = Запущена { $minutes } мин
+card-stop-game = ⏹ Остановить игру
+card-already-running = Уже запущена
 
 card-context-bookmarks = 🔖 Закладки...
 
@@ -157,3 +169,183 @@ bookmarks-mgmt-default-color = Цвет закладок по умолчанию
 bookmarks-mgmt-visible-limit = Кол-во закладок на обложке:
 bookmarks-mgmt-no-bookmarks = Закладок пока нет.
 
+# Launch profiles
+card-context-launch-profile = ▶ Профиль запуска
+launch-profiles-default = По умолчанию
+launch-profiles-edit = Редактировать профили...
+launch-profiles-title = Профили запуска
+launch-profiles-name = Название профиля
+launch-profiles-exe = Исполняемый файл
+launch-profiles-args = Аргументы
+launch-profiles-wrapper = Обёртка
+launch-profiles-workdir = Рабочая папка
+launch-profiles-env = Окружение
+launch-profiles-placeholders = Подстановки: {"{{"}path{"}}"}, {"{{"}dir{"}}"}, {"{{"}title{"}}"}, {"{{"}thread_id{"}}"}. Аргументы с пробелами берите в кавычки.
+launch-profiles-add = + Добавить профиль
+launch-profiles-save = Сохранить
+
+# Linux sandbox
+card-context-sandbox = 🛡 Песочница (без сети)
+card-context-sandbox-network = Разрешить сеть
+
+# Импорт существующих папок игр
+import-window-title = Импорт установленных игр
+import-choose-folder = Выбрать папку...
+import-searching = Поиск на F95...
+import-no-matches = Подходящие темы не найдены
+import-nothing-found = В этой папке не найдено новых папок с играми
+import-skip = Пропустить
+import-confirm = Импортировать выбранные ({ $count })
+
+# Install from a local archive
+card-context-install-from-file = 📦 Установить из файла...
+card-context-keep-archive = Сохранять архив после установки
+
+# Загрузка по ссылке
+card-context-download-url = 🔗 Скачать по ссылке...
+download-url-title = Скачать по ссылке
+download-url-hint = Вставьте ссылку на хостинг (pixeldrain, gofile, catbox, MEGA, F95 masked) или прямую ссылку на файл
+download-url-supported = Поддерживаемый хостинг
+download-url-plain-http = Будет скачан как обычный файл
+download-url-invalid = Некорректный http(s) URL
+download-url-unsupported-hosting = Эта masked-ссылка ведёт на неподдерживаемый хостинг
+download-url-start = Скачать
+
+# Капча на masked-ссылках
+card-captcha-text = Зеркало требует капчу. Откройте его в браузере, пройдите капчу и нажмите «Повторить». Тем временем пробуются другие зеркала.
+card-captcha-open = Открыть в браузере
+card-captcha-retry = Я прошёл капчу, повторить
+card-captcha = КАПЧА
+
+# Предпочтительная платформа загрузки
+settings-platform-order = Платформы для загрузки (берётся первая доступная):
+settings-platform-add = Добавить платформу...
+settings-platform-order-empty = Пусто: только { $platform } (эта ОС)
+card-context-choose-platform = ⬇ Скачать (выбрать платформу)...
+card-select-platform = ВЫБОР ПЛАТФОРМЫ
+
+# Предпочтения хостингов
+settings-hosting-order = Предпочтительные хостинги (пробуются первыми, в этом порядке):
+settings-hosting-add = Добавить хостинг...
+settings-hosting-blacklist = Никогда не скачивать с:
+settings-hosting-stats = { $ok } успешно / { $failed } с ошибкой
+settings-hosting-demoted = пробуется последним (часто ошибки)
+settings-hosting-reset-stats = Сбросить статистику хостингов
+
+# Link check
+card-context-check-links = 🔍 Проверить ссылки...
+link-check-title = Зеркала для скачивания
+link-check-running = Проверка ссылок...
+link-check-no-links = В теме не найдено ссылок для скачивания
+link-check-reachable = OK
+link-check-captcha = Капча
+link-check-dead = Недоступна
+link-check-unknown-size = размер неизвестен
+link-check-close = Закрыть
+
+# Verify files
+card-context-verify-files = ✔ Проверить файлы...
+verify-title = Установленные файлы
+verify-running = Проверка файлов...
+verify-no-manifest = Для этой игры нет записи об установке (установлена старой версией или импортирована), сравнивать не с чем.
+verify-ok = Все файлы ({ $count }) в порядке
+verify-missing = Отсутствует файлов: { $count }
+verify-changed = Изменено файлов: { $count }
+verify-repair = Восстановить
+verify-repair-hint = Скачать архив заново и восстановить только отсутствующие и изменённые файлы
+verify-repairing = Восстановление: загрузка архива...
+verify-repaired = Восстановлено файлов: { $count }
+
+# Extraction
+settings-extract-threads = Потоков распаковки zip:
+settings-extract-threads-auto = авто
+
+# 7z memory limit
+settings-sevenz-memory-limit = Лимит памяти для 7z:
+settings-sevenz-memory-unlimited = без лимита
+settings-sevenz-memory-limit-hint = 7z-архивы, словарю которых нужно больше памяти, отклоняются до распаковки, а не падают из-за нехватки памяти. 0 = без лимита.
+
+# Archive name encoding
+settings-archive-names = Кодировка имён в архивах:
+settings-archive-names-auto = Автоопределение
+settings-archive-names-hint = Используется для имён файлов в архивах, сохранённых не в UTF-8 (японские, китайские или русские игры, упакованные старыми программами). Выберите кодировку, если автоопределение дало искажённые имена, и распакуйте заново.
+
+# Archive library
+settings-archive-library = Сохранять скачанные архивы (библиотека архивов)
+settings-archive-library-hint = После распаковки архивы переносятся в папку библиотеки (по id темы и версии), а не удаляются. Игры можно переустановить или восстановить из неё без скачивания. Папку можно копировать на другие компьютеры.
+settings-archive-library-folder = Папка библиотеки архивов:
+settings-archive-library-limit = Лимит размера библиотеки:
+settings-archive-library-usage = Архивов: { $count }, { $size }
+settings-archive-library-clear = Удалить все сохранённые архивы
+card-context-install-from-library = Установить из библиотеки архивов ({ $version })
+
+# Downloads window
+downloads-window-title = Загрузки
+downloads-active = Активные ({ $count })
+downloads-queued = Прерванные ({ $count })
+downloads-history = История ({ $count })
+downloads-none = Пусто
+downloads-col-title = Игра
+downloads-col-phase = Статус
+downloads-col-bytes = Скачано
+downloads-col-speed = Скорость
+downloads-col-eta = Осталось
+downloads-col-hosting = Хостинг
+downloads-col-size = Размер
+downloads-col-duration = Заняло
+downloads-col-checksum = SHA-256
+downloads-col-finished = Завершено
+downloads-phase-starting = Запуск
+downloads-phase-downloading = Скачивание
+downloads-phase-extracting = Распаковка
+downloads-phase-choose = Ожидает выбора на карточке
+downloads-phase-captcha = Ожидает капчу
+downloads-phase-failed = Ошибка
+downloads-retry = Повторить
+downloads-dismiss = Убрать
+downloads-cancel = Отмена
+downloads-cancel-extracting = Распаковку нельзя отменить
+downloads-cancelled = Отменено
+downloads-captcha-retry = Капча пройдена, повторить
+downloads-start = Начать
+downloads-remove = Удалить
+downloads-open-folder = Открыть папку
+downloads-clear-history = Очистить историю
+downloads-local-file = локальный файл
+downloads-copy-checksum = { $sha } (нажмите, чтобы скопировать)
+downloads-finished-today = сегодня
+downloads-finished-days-ago = { $days } дн. назад
+
+# Bandwidth limit and download window
+settings-bandwidth-limit = Ограничение скорости загрузки:
+settings-bandwidth-limit-hint = Общий предел для всех загрузок. Отдельной загрузке можно задать свой предел в окне «Загрузки». 0 = без ограничения.
+settings-download-schedule = Загружать только с
+settings-download-schedule-hint = Загрузки, запущенные вне этого окна, ждут его начала, а идущие приостанавливаются, когда окно закрывается (местное время). Окно может переходить через полночь, например 23:00–07:00.
+downloads-col-limit = Предел скорости
+downloads-limit-none = без ограничения
+downloads-limit-hint = Предел скорости только для этой загрузки; общий предел из настроек тоже действует
+downloads-phase-scheduled = Ожидает окна загрузок
+card-scheduled-text = Ожидает окна загрузок ({ $window })
+card-scheduled = ПО РАСПИСАНИЮ
+
+# Segmented downloads
+settings-download-connections = Соединений на загрузку:
+settings-download-connections-hint = Скачивать большие файлы по нескольким соединениям сразу, если сервер это поддерживает (pixeldrain, catbox и другие хостинги, ограничивающие каждое соединение). 1 = одно соединение. С остальными серверами всегда используется одно.
+
+# Site endpoints
+settings-site-endpoints = Адрес сайта (для опытных)
+settings-site-base-url = Форум:
+settings-site-attachments-url = CDN вложений:
+settings-site-preview-url = CDN превью:
+settings-site-masked-path = Путь скрытых ссылок:
+settings-site-endpoints-hint = Пустые поля используют стандартный адрес f95zone. Меняйте их только для зеркала или локального тестового сервера; применяется после сохранения.
+settings-site-endpoints-env = Задано переменными окружения (F95_BASE_URL и другими); они важнее этих полей.
+
+# Network
+settings-network = Сеть
+settings-proxy = Прокси:
+settings-proxy-hint = http://, https://, socks5:// или socks5h:// (DNS через прокси), можно с user:password@. Пусто = прямое соединение.
+settings-hosting-proxy-separate = Отдельный прокси для файловых хостингов
+settings-hosting-proxy-separate-hint = Загрузки с файловых хостингов (Pixeldrain, Gofile, MEGA и другие) идут через прокси ниже; оставьте поле пустым, чтобы скачивать их напрямую.
+settings-hosting-proxy = Прокси для хостингов:
+settings-user-agent = User agent:
@@ -0,0 +1,136 @@
+// Forum session cookies. The forum client reads its Cookie header from `AppConfig.cookies` and
+// merges every Set-Cookie it receives from the forum back into it, so rotated xf_session/xf_csrf
+// values survive and are saved to app_config.json. Other hosts get no cookies.
+
+use reqwest::Url;
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+
+use crate::app::config::{APP_CONFIG, save_config_to_disk};
+
+/// Cookie store of the forum client, backed by `AppConfig.cookies`.
+pub struct SessionCookies;
+
+impl CookieStore for SessionCookies {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        if !is_forum(url) {
+            return;
+        }
+        let changed = {
+            let mut cfg = APP_CONFIG.write().unwrap();
+            let current = cfg.cookies.clone().unwrap_or_default();
+            // Without a session there is nothing to keep fresh; stray cookies would also hide
+            // the login screen
+            if current.trim().is_empty() {
+                return;
+            }
+            let updated = cookie_headers
+                .filter_map(|h| h.to_str().ok())
+                .fold(current.clone(), |header, set_cookie| {
+                    apply_set_cookie(&header, set_cookie)
+                });
+            if updated == current {
+                false
+            } else {
+                cfg.cookies = Some(updated);
+                true
+            }
+        };
+        if changed {
+            log::info!("Forum session cookies refreshed");
+            save_config_to_disk();
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        if !is_forum(url) {
+            return None;
+        }
+        let header = crate::parser::game_info::cookies();
+        if header.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+fn is_forum(url: &Url) -> bool {
+    let base = crate::net::endpoints::current().base;
+    Url::parse(&base).is_ok_and(|base| {
+        base.host_str() == url.host_str()
+            && base.port_or_known_default() == url.port_or_known_default()
+    })
+}
+
+/// Apply one Set-Cookie header to a "name=value; name=value" Cookie header. A cookie set to
+/// expire (Max-Age <= 0, or PHP's "deleted" value) is removed; other attributes are ignored.
+fn apply_set_cookie(header: &str, set_cookie: &str) -> String {
+    let mut parts = set_cookie.split(';');
+    let Some((name, value)) = parts.next().and_then(|p| p.split_once('=')) else {
+        return header.to_string();
+    };
+    let (name, value) = (name.trim(), value.trim());
+    if name.is_empty() {
+        return header.to_string();
+    }
+    let expired = value.is_empty()
+        || value == "deleted"
+        || parts.any(|attr| {
+            attr.split_once('=').is_some_and(|(k, v)| {
+                k.trim().eq_ignore_ascii_case("max-age")
+                    && v.trim().parse::<i64>().is_ok_and(|age| age <= 0)
+            })
+        });
+
+    let mut cookies: Vec<(&str, &str)> = header
+        .split(';')
+        .filter_map(|c| c.split_once('='))
+        .map(|(n, v)| (n.trim(), v.trim()))
+        .filter(|(n, _)| !n.is_empty())
+        .collect();
+    match cookies.iter().position(|(n, _)| *n == name) {
+        Some(i) if expired => {
+            cookies.remove(i);
+        }
+        Some(i) => cookies[i].1 = value,
+        None if !expired => cookies.push((name, value)),
+        None => {}
+    }
+    cookies
+        .iter()
+        .map(|(n, v)| format!("{n}={v}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_set_cookie() {
+        let header = "xf_csrf=old; xf_session=s1; xf_user=42%2Cabc";
+        assert_eq!(
+            apply_set_cookie(header, "xf_session=s2; path=/; secure; HttpOnly"),
+            "xf_csrf=old; xf_session=s2; xf_user=42%2Cabc"
+        );
+        assert_eq!(
+            apply_set_cookie(header, "xf_notice_dismiss=1; Max-Age=3600"),
+            "xf_csrf=old; xf_session=s1; xf_user=42%2Cabc; xf_notice_dismiss=1"
+        );
+        assert_eq!(
+            apply_set_cookie(
+                header,
+                "xf_user=deleted; expires=Thu, 01-Jan-1970 00:00:01 GMT; Max-Age=0; path=/"
+            ),
+            "xf_csrf=old; xf_session=s1"
+        );
+        assert_eq!(
+            apply_set_cookie(header, "xf_csrf=new; max-age=-1"),
+            "xf_session=s1; xf_user=42%2Cabc"
+        );
+        // Values may contain '='
+        assert_eq!(apply_set_cookie("a=1", "b=x==; path=/"), "a=1; b=x==");
+        assert_eq!(apply_set_cookie(header, "garbage"), header);
+    }
+}
@@ -0,0 +1,145 @@
+// Site endpoints: the forum, its attachments and preview CDNs and the masked-link path. Each can
+// be overridden in settings or, taking precedence, by an environment variable, so the app can be
+// pointed at a mirror domain or at a local mock server.
+
+use lazy_static::lazy_static;
+
+use crate::app::settings::with_settings;
+
+pub const DEFAULT_BASE_URL: &str = "https://f95zone.to/";
+pub const DEFAULT_ATTACHMENTS_URL: &str = "https://attachments.f95zone.to/";
+pub const DEFAULT_PREVIEW_URL: &str = "https://preview.f95zone.to/";
+pub const DEFAULT_MASKED_PATH: &str = "masked";
+
+lazy_static! {
+    static ref ENV_OVERRIDES: Overrides = Overrides {
+        base: env_value("F95_BASE_URL"),
+        attachments: env_value("F95_ATTACHMENTS_URL"),
+        preview: env_value("F95_PREVIEW_URL"),
+        masked_path: env_value("F95_MASKED_PATH"),
+    };
+}
+
+/// Per-endpoint overrides; empty = default
+#[derive(Debug, Default, Clone)]
+struct Overrides {
+    base: String,
+    attachments: String,
+    preview: String,
+    masked_path: String,
+}
+
+/// Resolved endpoints. URLs always end with '/'; the masked path has no slashes around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+    pub base: String,
+    pub attachments: String,
+    pub preview: String,
+    pub masked_path: String,
+}
+
+impl Endpoints {
+    fn resolve(env: &Overrides, settings: &Overrides) -> Self {
+        let pick = |env: &str, settings: &str, default: &str| {
+            [env, settings, default]
+                .into_iter()
+                .map(str::trim)
+                .find(|v| !v.is_empty())
+                .unwrap_or(default)
+                .to_string()
+        };
+        Self {
+            base: with_slash(pick(&env.base, &settings.base, DEFAULT_BASE_URL)),
+            attachments: with_slash(pick(
+                &env.attachments,
+                &settings.attachments,
+                DEFAULT_ATTACHMENTS_URL,
+            )),
+            preview: with_slash(pick(&env.preview, &settings.preview, DEFAULT_PREVIEW_URL)),
+            masked_path: pick(&env.masked_path, &settings.masked_path, DEFAULT_MASKED_PATH)
+                .trim_matches('/')
+                .to_string(),
+        }
+    }
+
+    /// `path` on the forum ("login/login" -> "https://f95zone.to/login/login").
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base, path.trim_start_matches('/'))
+    }
+
+    pub fn thread_url(&self, thread_id: u64) -> String {
+        self.url(&format!("threads/{thread_id}/"))
+    }
+}
+
+/// Endpoints from the environment and the current settings.
+pub fn current() -> Endpoints {
+    let settings = with_settings(|st| Overrides {
+        base: st.site_base_url.clone(),
+        attachments: st.site_attachments_url.clone(),
+        preview: st.site_preview_url.clone(),
+        masked_path: st.site_masked_path.clone(),
+    });
+    Endpoints::resolve(&ENV_OVERRIDES, &settings)
+}
+
+/// Whether an endpoint is set by an environment variable (the settings field is then ignored).
+pub fn env_overridden() -> bool {
+    let env = &*ENV_OVERRIDES;
+    [&env.base, &env.attachments, &env.preview, &env.masked_path]
+        .iter()
+        .any(|v| !v.trim().is_empty())
+}
+
+fn env_value(name: &str) -> String {
+    std::env::var(name).unwrap_or_default()
+}
+
+fn with_slash(mut url: String) -> String {
+    if !url.ends_with('/') {
+        url.push('/');
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_endpoints() {
+        let defaults = Endpoints::resolve(&Overrides::default(), &Overrides::default());
+        assert_eq!(defaults.base, DEFAULT_BASE_URL);
+        assert_eq!(defaults.masked_path, "masked");
+        assert_eq!(
+            defaults.thread_url(267483),
+            "https://f95zone.to/threads/267483/"
+        );
+        assert_eq!(
+            defaults.url("/login/login"),
+            "https://f95zone.to/login/login"
+        );
+
+        let settings = Overrides {
+            base: "http://127.0.0.1:8095".to_string(),
+            preview: " https://preview.mirror.example ".to_string(),
+            masked_path: "/go/".to_string(),
+            ..Default::default()
+        };
+        let from_settings = Endpoints::resolve(&Overrides::default(), &settings);
+        assert_eq!(from_settings.base, "http://127.0.0.1:8095/");
+        assert_eq!(from_settings.attachments, DEFAULT_ATTACHMENTS_URL);
+        assert_eq!(from_settings.preview, "https://preview.mirror.example/");
+        assert_eq!(from_settings.masked_path, "go");
+
+        // The environment wins over settings
+        let env = Overrides {
+            base: "https://mirror.example/".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            Endpoints::resolve(&env, &settings).base,
+            "https://mirror.example/"
+        );
+    }
+}
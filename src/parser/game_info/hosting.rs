@@ -146,6 +146,13 @@ impl TryFrom<Url> for HostingSubset {
 }
 
 impl HostingSubset {
+    pub const ALL: [HostingSubset; 4] = [
+        HostingSubset::Pixeldrain,
+        HostingSubset::Gofile,
+        HostingSubset::Mega,
+        HostingSubset::Catbox,
+    ];
+
     pub fn base(&self) -> &'static str {
         let hosting: Hosting = (*self).into();
         hosting.base()
@@ -4,24 +4,28 @@ use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
 
 use crate::app::settings::APP_SETTINGS;
 use crate::{
-    game_download::{GameDownloadStatus, Progress},
+    game_download::{GameDownloadStatus, Progress, archive_store, throttle::DownloadThrottle},
     parser::game_info::HostingSubset,
 };
 
 use self::info::DirectRequest;
-use super::cookies;
 
 mod archive;
 pub mod direct;
 mod download;
 pub mod gofile;
 pub mod info;
+mod probe;
+mod segmented;
+mod space;
+mod validate;
 
 // Futures-IO writer adapter for MEGA -> tokio::fs::File
 use futures::io as futures_io;
 use futures_io::AsyncWrite as FuturesAsyncWrite;
 use std::{
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 use tokio::io::AsyncWrite as TokioAsyncWrite;
@@ -32,15 +36,25 @@ struct MegaFileWriter {
     sd: UnboundedSender<GameDownloadStatus>,
     total: u64,
     written: u64,
+    throttle: Arc<DownloadThrottle>,
+    /// Wait owed to the speed cap (or for the download window), served before the next write
+    pause: Option<Pin<Box<dyn Future<Output = ()> + Send>>>,
 }
 
 impl MegaFileWriter {
-    fn new(file: tokio::fs::File, sd: UnboundedSender<GameDownloadStatus>, total: u64) -> Self {
+    fn new(
+        file: tokio::fs::File,
+        sd: UnboundedSender<GameDownloadStatus>,
+        total: u64,
+        throttle: Arc<DownloadThrottle>,
+    ) -> Self {
         Self {
             file,
             sd,
             total,
             written: 0,
+            throttle,
+            pause: None,
         }
     }
 }
@@ -53,15 +67,35 @@ impl FuturesAsyncWrite for MegaFileWriter {
     ) -> Poll<Result<usize, futures_io::Error>> {
         // Safety: MegaFileWriter is pinned solely to protect inner `file` pin projection here.
         let this = unsafe { self.get_unchecked_mut() };
+        if let Some(pause) = this.pause.as_mut() {
+            if pause.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            this.pause = None;
+        }
         let mut pinned = Pin::new(&mut this.file);
         match TokioAsyncWrite::poll_write(pinned.as_mut(), cx, buf) {
             Poll::Ready(Ok(n)) => {
                 this.written += n as u64;
-                if this.total > 0 {
-                    let progress = (this.written as f32) / (this.total as f32);
-                    let _ = this
-                        .sd
-                        .send(GameDownloadStatus::Downloading(Progress::Pending(progress)));
+                let wait = this.throttle.reserve(n as u64);
+                if !wait.is_zero() || !crate::game_download::throttle::window_open() {
+                    let throttle = this.throttle.clone();
+                    let sd = this.sd.clone();
+                    this.pause = Some(Box::pin(async move {
+                        tokio::time::sleep(wait).await;
+                        throttle.hold_while_closed(&sd).await;
+                    }));
+                }
+                let transferred = GameDownloadStatus::Transferred {
+                    done: this.written,
+                    total: this.total,
+                };
+                if this.sd.send(transferred).is_err() {
+                    // The download was cancelled; failing the write stops the transfer
+                    return Poll::Ready(Err(futures_io::Error::new(
+                        futures_io::ErrorKind::Interrupted,
+                        "download cancelled",
+                    )));
                 }
                 Poll::Ready(Ok(n))
             }
@@ -103,18 +137,23 @@ impl FuturesAsyncWrite for MegaFileWriter {
     }
 }
 
+pub use self::archive::{extract_archive, find_first_exe};
 pub use self::direct::DirectDownloadLink;
 pub use self::info::DownloadLinkInfo;
+pub use self::probe::{LinkProbe, ProbeStatus};
+pub use self::space::format_size;
 
 #[derive(Debug, Clone)]
 pub enum DownloadLink {
     Direct(DirectDownloadLink),
     Masked(Url),
+    /// Plain HTTP(S) file URL pasted by the user (no hosting-specific handling)
+    Http(Url),
 }
 impl DownloadLink {
     pub fn new(value: Url) -> Option<DownloadLink> {
         if let Some(mut segs) = value.path_segments() {
-            if segs.next() == Some("masked") {
+            if segs.next() == Some(masked_path().as_str()) {
                 // Validate masked target hosting is supported (e.g. skip workupload, mediafire, etc. if not in subset)
                 if let Some(host) = segs.next() {
                     let host_url_str = format!("https://{host}");
@@ -135,6 +174,54 @@ impl DownloadLink {
         }
         Some(Self::Direct(DirectDownloadLink::new(value)?))
     }
+
+    /// Domain of the hosting behind this link ("pixeldrain.com"), used for ranking and stats.
+    /// Masked links report their target hosting.
+    pub fn hosting_name(&self) -> String {
+        match self {
+            DownloadLink::Direct(d) => d.hosting.to_string(),
+            DownloadLink::Masked(u) => {
+                let host = u.path_segments().and_then(|mut s| s.nth(1)).unwrap_or_default();
+                Url::from_str(&format!("https://{host}"))
+                    .ok()
+                    .and_then(|url| HostingSubset::try_from(url).ok())
+                    .map(|h| h.to_string())
+                    .unwrap_or_else(|| host.to_string())
+            }
+            DownloadLink::Http(u) => u.host_str().unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Parse a URL entered by the user: a supported hosting (or F95 masked) link, otherwise
+    /// any http(s) URL is downloaded as a plain file. A masked link to an unsupported hosting is
+    /// rejected (see [`Self::is_masked_input`]): fetching it would only save the forum's page.
+    pub fn from_user_input(input: &str) -> Option<DownloadLink> {
+        let url = Url::from_str(input.trim()).ok()?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return None;
+        }
+        match Self::new(url.clone()) {
+            Some(link) => Some(link),
+            None if is_masked_url(&url) => None,
+            None => Some(Self::Http(url)),
+        }
+    }
+
+    /// Whether user input is a masked link, so a rejected one can be reported as an
+    /// unsupported hosting rather than an invalid URL.
+    pub fn is_masked_input(input: &str) -> bool {
+        Url::from_str(input.trim()).is_ok_and(|url| is_masked_url(&url))
+    }
+}
+
+fn masked_path() -> String {
+    crate::net::endpoints::current().masked_path
+}
+
+fn is_masked_url(url: &Url) -> bool {
+    url.path_segments()
+        .and_then(|mut segs| segs.next())
+        .is_some_and(|seg| seg == masked_path())
 }
 
 #[derive(Debug)]
@@ -174,7 +261,6 @@ pub enum DownloadError {
     Captcha,
     ClientBuild(reqwest::Error),
     Request(reqwest::Error),
-    MissingHeader(&'static str),
     Io(std::io::Error),
     StartTask,
     InvalidUrl,
@@ -184,7 +270,51 @@ pub enum DownloadError {
     MegaFetchNodes,
     MegaNoFileNode,
     MegaDownload,
-    FilenameParse,
+    NotEnoughSpace(String),
+    /// Hosting answered with a download/bandwidth quota page
+    QuotaExceeded,
+    /// Hosting says the file was deleted or never existed
+    FileRemoved,
+    /// Response is a page (content type given), not the file
+    NotAnArchive(String),
+    /// File is an archive format extraction doesn't handle (extension given)
+    UnsupportedArchive(&'static str),
+}
+
+impl DownloadError {
+    /// Whether the hosting is to blame: a network or HTTP failure talking to it, or an answer
+    /// without the file. Local problems (disk, client setup, bad links) and captchas aren't.
+    pub fn is_hosting_failure(&self) -> bool {
+        match self {
+            DownloadError::Network(_)
+            | DownloadError::Request(_)
+            | DownloadError::NoRedirect
+            | DownloadError::InvalidRedirectUrl
+            | DownloadError::JsonParse
+            | DownloadError::MegaFetchNodes
+            | DownloadError::MegaNoFileNode
+            | DownloadError::MegaDownload
+            | DownloadError::QuotaExceeded
+            | DownloadError::FileRemoved
+            | DownloadError::NotAnArchive(_) => true,
+            DownloadError::DirectLinkFailed(DirectLinkError::Gofile(e)) => !matches!(
+                e,
+                gofile::GofileLinkError::MissingFolderId
+                    | gofile::GofileLinkError::InvalidFileUrl(_)
+                    | gofile::GofileLinkError::InvalidCookieHeader(_)
+            ),
+            DownloadError::DirectLinkFailed(_)
+            | DownloadError::UnsupportedHosting
+            | DownloadError::Captcha
+            | DownloadError::ClientBuild(_)
+            | DownloadError::Io(_)
+            | DownloadError::StartTask
+            | DownloadError::InvalidUrl
+            | DownloadError::MegaClientBuild
+            | DownloadError::NotEnoughSpace(_)
+            | DownloadError::UnsupportedArchive(_) => false,
+        }
+    }
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -197,6 +327,7 @@ impl DownloadLink {
     async fn get_direct(self) -> Result<DirectDownloadLink, DownloadError> {
         match self {
             DownloadLink::Direct(link) => Ok(link),
+            DownloadLink::Http(_) => Err(DownloadError::UnsupportedHosting),
             DownloadLink::Masked(link) => {
                 //check is hosting valid
                 {
@@ -217,7 +348,6 @@ impl DownloadLink {
                         "Content-Type",
                         "application/x-www-form-urlencoded; charset=UTF-8",
                     )
-                    .header("Cookie", cookies())
                     .body("xhr=1&download=1")
                     .send()
                     .await
@@ -238,32 +368,45 @@ impl DownloadLink {
         }
     }
 
-    pub async fn download(&self) -> Result<UnboundedReceiver<GameDownloadStatus>, DownloadError> {
+    pub async fn download(
+        &self,
+        throttle: &Arc<DownloadThrottle>,
+    ) -> Result<UnboundedReceiver<GameDownloadStatus>, DownloadError> {
         let (sd, rc) = unbounded_channel();
 
         // Resolve direct request (either direct HTTP or MEGA public URL)
-        let direct_req = {
-            let direct = self.clone().get_direct().await?;
-            direct
-                .clone()
-                .get()
-                .await
-                .map_err(DownloadError::DirectLinkFailed)?
+        let direct_req = match self {
+            DownloadLink::Http(url) => {
+                DirectRequest::Http(reqwest::Request::new(reqwest::Method::GET, url.clone()))
+            }
+            _ => {
+                let direct = self.clone().get_direct().await?;
+                direct
+                    .clone()
+                    .get()
+                    .await
+                    .map_err(DownloadError::DirectLinkFailed)?
+            }
         };
 
         // Fire request / or branch for MEGA
-        let client = crate::net::client();
-        let resp = match direct_req {
-            DirectRequest::Http(request) => client
-                .execute(request)
-                .await
-                .map_err(DownloadError::Request)?,
+        let client = crate::net::hosting_client();
+        // The request is kept to ask for byte ranges of the same file (segmented download)
+        let (mut resp, range_request) = match direct_req {
+            DirectRequest::Http(request) => {
+                let range_request = request.try_clone();
+                let resp = client
+                    .execute(request)
+                    .await
+                    .map_err(DownloadError::Request)?;
+                (resp, range_request)
+            }
             DirectRequest::MegaPublicUrl(url) => {
                 // MEGA public link handling: fetch nodes and download via mega::Client to disk.
                 log::info!("downloading from {}", url.as_str());
 
                 // 1) Init MEGA client over reqwest
-                let http_client = crate::net::client().clone();
+                let http_client = crate::net::hosting_client();
                 let mega_client = match mega::ClientBuilder::new().https(true).build(http_client) {
                     Ok(c) => c,
                     Err(e) => {
@@ -293,6 +436,8 @@ impl DownloadLink {
                 };
 
                 // 4) Prepare output file path using node name
+                space::check_download_space(file_node.size())
+                    .map_err(DownloadError::NotEnoughSpace)?;
                 let filename = file_node.name().to_string();
                 let download_dir = {
                     let s = APP_SETTINGS.read().unwrap();
@@ -307,9 +452,14 @@ impl DownloadLink {
                     .map_err(DownloadError::Io)?;
 
                 // 5) Start MEGA download into writer that updates progress
-                let writer = MegaFileWriter::new(file, sd.clone(), file_node.size());
+                let writer =
+                    MegaFileWriter::new(file, sd.clone(), file_node.size(), throttle.clone());
                 if let Err(e) = mega_client.download_node(file_node, writer).await {
                     log::error!("mega download_node error: {:?}", e);
+                    // Drop the partial file so a failed transfer doesn't hold disk space
+                    if let Err(e) = tokio::fs::remove_file(&filepath).await {
+                        log::warn!("Failed to remove partial {}: {}", filepath.display(), e);
+                    }
                     return Err(DownloadError::MegaDownload);
                 }
 
@@ -333,15 +483,13 @@ impl DownloadLink {
 
                 match unzip_res {
                     Ok(Ok((dest_dir, exe_path))) => {
-                        // Delete the original archive after successful extraction
-                        if let Err(e) = std::fs::remove_file(&archive_path) {
-                            log::warn!(
-                                "Failed to delete archive {}: {}",
-                                archive_path.display(),
-                                e
-                            );
-                        }
-                        let _ = sd.send(GameDownloadStatus::Completed { dest_dir, exe_path });
+                        // Delete the original archive (or hand it to the archive library)
+                        let archive = archive_store::keep_or_delete(&archive_path);
+                        let _ = sd.send(GameDownloadStatus::Completed {
+                            dest_dir,
+                            exe_path,
+                            archive,
+                        });
                     }
                     Ok(Err(msg)) => {
                         let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Error(msg)));
@@ -358,30 +506,16 @@ impl DownloadLink {
             }
         };
 
-        let filename_fallback = resp.url().path().split('/').last();
-
-        // Extract filename
-        let header = resp
-            .headers()
-            .get("content-disposition")
-            .map(|e| e.to_str().unwrap())
-            .or(filename_fallback)
-            .ok_or(DownloadError::MissingHeader("content-disposition"))?;
-        let filename = header
-            .replace('"', "")
-            .replace('\\', "")
-            .replace('/', "")
-            .replace(':', "")
-            .replace('*', "")
-            .replace('?', "")
-            .replace('<', "")
-            .replace('>', "")
-            .replace('|', "")
-            .split('=')
-            .nth(1)
-            .or(filename_fallback)
-            .ok_or(DownloadError::FilenameParse)?
-            .to_owned();
+        // Read the total size before sniffing consumes the first bytes
+        let content_length = resp.content_length();
+        let (prefix, kind) = validate::validate_response(&mut resp).await?;
+
+        let filename =
+            download_filename(resp.headers().get("content-disposition"), resp.url(), kind);
+
+        if let Some(size) = content_length {
+            space::check_download_space(size).map_err(DownloadError::NotEnoughSpace)?;
+        }
 
         // Prepare file (use user-configured temp dir)
         let download_dir = {
@@ -396,12 +530,134 @@ impl DownloadLink {
             .await
             .map_err(DownloadError::Io)?;
 
-        // Start streaming to disk
+        // Start streaming to disk, over several connections when the server allows it
         log::info!("downloading from {}", resp.url().as_str());
-        if !download::start_download_task(resp, sd, file, filepath.clone()).await {
-            return Err(DownloadError::StartTask);
+        let connections = APP_SETTINGS.read().unwrap().download_connections;
+        let segments = content_length
+            .filter(|_| connections > 1 && segmented::accepts_ranges(&resp))
+            .map(|size| (size, segmented::plan_segments(size, connections)))
+            .filter(|(_, ranges)| ranges.len() > 1);
+        if let (Some((size, ranges)), Some(request)) = (segments, range_request) {
+            let target = segmented::SegmentedTarget {
+                file,
+                filepath: filepath.clone(),
+                prefix,
+                total: size,
+                ranges,
+            };
+            if !segmented::start_segmented_task(resp, request, sd, target, throttle.clone()).await {
+                return Err(DownloadError::StartTask);
+            }
+            return Ok(rc);
         }
+        download::start_download_task(
+            resp,
+            sd,
+            file,
+            filepath.clone(),
+            prefix,
+            content_length,
+            throttle.clone(),
+        )
+        .await;
 
         Ok(rc)
     }
 }
+
+/// Name to save a download under: the Content-Disposition filename, else the last URL segment
+/// (percent-decoded), else a generated one. Characters file systems reject are dropped.
+fn download_filename(
+    disposition: Option<&reqwest::header::HeaderValue>,
+    url: &Url,
+    kind: Option<validate::FileKind>,
+) -> String {
+    let from_header = disposition.and_then(|v| {
+        String::from_utf8_lossy(v.as_bytes())
+            .split('=')
+            .nth(1)
+            .map(str::to_owned)
+    });
+    let from_url = url
+        .path_segments()
+        .and_then(|mut s| s.next_back())
+        .map(|s| {
+            percent_encoding::percent_decode_str(s)
+                .decode_utf8_lossy()
+                .into_owned()
+        });
+    let filename = [from_header, from_url]
+        .into_iter()
+        .flatten()
+        .map(|name| {
+            name.replace(['"', '\\', '/', ':', '*', '?', '<', '>', '|'], "")
+                .trim()
+                .to_owned()
+        })
+        .find(|name| !name.is_empty() && name != "." && name != "..")
+        .unwrap_or_else(|| format!("download-{}", uuid::Uuid::new_v4()));
+    match kind {
+        // Name without a usable extension (e.g. an id): extraction picks the format by it
+        Some(kind) if !has_extension(&filename, kind.extension()) => {
+            format!("{filename}.{}", kind.extension())
+        }
+        _ => filename,
+    }
+}
+
+fn has_extension(filename: &str, ext: &str) -> bool {
+    filename
+        .rsplit_once('.')
+        .is_some_and(|(_, e)| e.eq_ignore_ascii_case(ext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_link_from_user_input() {
+        assert!(matches!(
+            DownloadLink::from_user_input(" https://pixeldrain.com/u/abc123 "),
+            Some(DownloadLink::Direct(_))
+        ));
+        assert!(matches!(
+            DownloadLink::from_user_input("https://example.com/files/Game-1.0-pc.zip"),
+            Some(DownloadLink::Http(_))
+        ));
+        assert!(DownloadLink::from_user_input("ftp://example.com/game.zip").is_none());
+        // A masked link to an unsupported hosting isn't fetched as a plain file
+        let unsupported = "https://f95zone.to/masked/workupload.com/file/abc";
+        assert!(DownloadLink::from_user_input(unsupported).is_none());
+        assert!(DownloadLink::is_masked_input(unsupported));
+        assert!(matches!(
+            DownloadLink::from_user_input("https://f95zone.to/masked/mega.nz/abc/def"),
+            Some(DownloadLink::Masked(_))
+        ));
+        assert!(DownloadLink::from_user_input("not a url").is_none());
+    }
+
+    #[test]
+    fn test_hosting_name_of_masked_link() {
+        let masked =
+            DownloadLink::new(Url::from_str("https://f95zone.to/masked/mega.nz/abc/def").unwrap());
+        assert_eq!(masked.map(|l| l.hosting_name()).as_deref(), Some("mega.nz"));
+        let direct = DownloadLink::from_user_input("https://pixeldrain.com/u/abc123").unwrap();
+        assert_eq!(direct.hosting_name(), "pixeldrain.com");
+    }
+
+    #[test]
+    fn test_download_filename() {
+        let url = Url::from_str("https://example.com/files/My%20Game-1.0.zip").unwrap();
+        let header = reqwest::header::HeaderValue::from_bytes(
+            "attachment; filename=\"Игра-1.0.zip\"".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(download_filename(Some(&header), &url, None), "Игра-1.0.zip");
+        assert_eq!(download_filename(None, &url, None), "My Game-1.0.zip");
+
+        let dir = Url::from_str("https://example.com/files/").unwrap();
+        let generated = download_filename(None, &dir, Some(validate::FileKind::Zip));
+        assert!(generated.starts_with("download-") && generated.ends_with(".zip"));
+    }
+}
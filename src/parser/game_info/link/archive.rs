@@ -1,16 +1,18 @@
-use sevenz_rust;
 use std::{
-    collections::HashSet,
     fs,
-    fs::File as StdFile,
-    io::{Read, Write},
     path::{Path, PathBuf},
 };
 use tokio::sync::mpsc::UnboundedSender;
 use unrar;
-use zip::ZipArchive;
 
-use crate::game_download::{GameDownloadStatus, Progress};
+use crate::app::settings::store::ArchiveNameEncoding;
+use crate::game_download::{GameDownloadStatus, Progress, integrity};
+
+use super::space;
+
+mod names;
+mod sevenz;
+mod unzip;
 
 fn sanitize_relative_path(name: &str, strip_prefix: Option<&str>) -> Option<PathBuf> {
     // Nested helpers are kept local to avoid polluting the module namespace.
@@ -98,7 +100,7 @@ fn sanitize_relative_path(name: &str, strip_prefix: Option<&str>) -> Option<Path
     Some(out)
 }
 
-fn find_first_exe(dir: &Path) -> Option<PathBuf> {
+pub fn find_first_exe(dir: &Path) -> Option<PathBuf> {
     fn rec(cur: &Path) -> Option<PathBuf> {
         let entries = std::fs::read_dir(cur).ok()?;
         for e in entries {
@@ -139,179 +141,6 @@ fn find_first_exe(dir: &Path) -> Option<PathBuf> {
     rec(dir)
 }
 
-fn unzip_streaming(
-    zip_path: &Path,
-    dest_base: &Path,
-    sd: &UnboundedSender<GameDownloadStatus>,
-) -> Result<(PathBuf, Option<PathBuf>), String> {
-    // Only process .zip files
-    if zip_path
-        .extension()
-        .and_then(|e| e.to_str())
-        .map(|s| s.eq_ignore_ascii_case("zip"))
-        != Some(true)
-    {
-        return Ok((dest_base.to_path_buf(), None));
-    }
-
-    let file = StdFile::open(zip_path).map_err(|e| format!("Open zip failed: {e}"))?;
-    let mut archive = ZipArchive::new(file).map_err(|e| format!("Read zip failed: {e}"))?;
-
-    // Detect single top-level folder and whether there are root files
-    let mut top_levels: HashSet<String> = HashSet::new();
-    let mut root_files = false;
-    for i in 0..archive.len() {
-        let name_owned = archive
-            .by_index(i)
-            .map_err(|e| format!("Zip idx {i} err: {e}"))?
-            .name()
-            .to_string();
-        let n = name_owned.replace('\\', "/");
-        if let Some(pos) = n.find('/') {
-            let top = &n[..pos];
-            if !top.is_empty() {
-                top_levels.insert(top.to_string());
-            }
-        } else {
-            root_files = true;
-            if !n.is_empty() {
-                top_levels.insert(n.clone());
-            }
-        }
-    }
-    let strip_prefix = if !root_files && top_levels.len() == 1 {
-        Some(format!("{}/", top_levels.iter().next().unwrap()))
-    } else {
-        None
-    };
-
-    // Destination: use archive_dest_dir(...) and ensure uniqueness to avoid mixing previous runs
-    let base_dest = archive_dest_dir(zip_path, dest_base);
-    let mut dest_dir = base_dest.clone();
-    if dest_dir.exists() {
-        let orig_name = dest_dir
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("extracted")
-            .to_string();
-        let mut idx = 2usize;
-        loop {
-            let candidate = dest_dir.with_file_name(format!("{orig_name}-{idx}"));
-            if !candidate.exists() {
-                dest_dir = candidate;
-                break;
-            }
-            idx += 1;
-        }
-    }
-    fs::create_dir_all(&dest_dir).map_err(|e| format!("Create dest dir failed: {e}"))?;
-
-    // Count total bytes to extract (exclude dirs, after sanitize)
-    let mut total_bytes: u64 = 0;
-    for i in 0..archive.len() {
-        let f = archive
-            .by_index(i)
-            .map_err(|e| format!("Zip idx {i} err: {e}"))?;
-        if f.is_dir() {
-            continue;
-        }
-        if let Some(rel) = sanitize_relative_path(f.name(), strip_prefix.as_deref()) {
-            if !rel.as_os_str().is_empty() {
-                total_bytes = total_bytes.saturating_add(f.size());
-            }
-        }
-    }
-
-    // Extract sequentially with streaming I/O
-    let mut extracted_bytes: u64 = 0;
-    // Track case-insensitive created file paths to avoid collisions on Windows
-    let mut used_rel_lower: HashSet<String> = HashSet::new();
-
-    for i in 0..archive.len() {
-        let mut f = archive
-            .by_index(i)
-            .map_err(|e| format!("Zip idx {i} err: {e}"))?;
-        let name = f.name().to_string();
-        let is_dir = f.is_dir();
-
-        let rel = match sanitize_relative_path(&name, strip_prefix.as_deref()) {
-            Some(p) => p,
-            None => continue,
-        };
-        let mut out_path = dest_dir.join(&rel);
-
-        if is_dir {
-            if let Err(e) = fs::create_dir_all(&out_path) {
-                log::warn!("Create dir {} failed: {}", out_path.display(), e);
-            }
-            continue;
-        }
-
-        // Ensure parent directories exist
-        if let Some(parent) = out_path.parent() {
-            std::fs::create_dir_all(parent)
-                .map_err(|e| format!("Create parent {} failed: {}", parent.display(), e))?;
-        }
-
-        // Avoid case-insensitive collisions
-        let mut rel_key = rel.to_string_lossy().to_ascii_lowercase();
-        if used_rel_lower.contains(&rel_key) {
-            // Append (2), (3)... before extension
-            let file_name = rel.file_name().and_then(|s| s.to_str()).unwrap_or("file");
-            let (stem, ext_opt) = match file_name.rsplit_once('.') {
-                Some((st, ex)) if !st.is_empty() => (st.to_string(), Some(ex.to_string())),
-                _ => (file_name.to_string(), None),
-            };
-            let mut n = 2usize;
-            loop {
-                let mut new_name = format!("{stem} ({n})");
-                if let Some(ex) = &ext_opt {
-                    new_name.push('.');
-                    new_name.push_str(ex);
-                }
-                let candidate_rel = rel.with_file_name(new_name);
-                let candidate_key = candidate_rel.to_string_lossy().to_ascii_lowercase();
-                if !used_rel_lower.contains(&candidate_key) {
-                    out_path = dest_dir.join(&candidate_rel);
-                    rel_key = candidate_key;
-                    break;
-                }
-                n += 1;
-            }
-        }
-        used_rel_lower.insert(rel_key);
-
-        // Stream data from ZipFile to disk with a fixed-size buffer
-        let mut out_file = StdFile::create(&out_path)
-            .map_err(|e| format!("Create {} failed: {}", out_path.display(), e))?;
-
-        let mut buf = [0u8; 64 * 1024];
-        loop {
-            match Read::read(&mut f, &mut buf) {
-                Ok(0) => break,
-                Ok(n) => {
-                    out_file
-                        .write_all(&buf[..n])
-                        .map_err(|e| format!("Write {} failed: {}", out_path.display(), e))?;
-                    extracted_bytes = extracted_bytes.saturating_add(n as u64);
-                    let progress = if total_bytes == 0 {
-                        1.0
-                    } else {
-                        (extracted_bytes as f32) / (total_bytes as f32)
-                    };
-                    let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(progress)));
-                }
-                Err(e) => return Err(format!("Read entry {} failed: {}", name, e)),
-            }
-        }
-    }
-
-    // Ensure final 100% notification
-    let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(1.0)));
-
-    Ok((dest_dir.clone(), find_first_exe(&dest_dir)))
-}
-
 fn archive_dest_dir(archive_path: &Path, dest_base: &Path) -> PathBuf {
     let fname = archive_path
         .file_name()
@@ -336,31 +165,34 @@ fn archive_dest_dir(archive_path: &Path, dest_base: &Path) -> PathBuf {
     dest_base.join(stem)
 }
 
-fn install_standalone_executable(
-    executable_path: &Path,
-    dest_base: &Path,
-    sd: &UnboundedSender<GameDownloadStatus>,
-) -> Result<(PathBuf, Option<PathBuf>), String> {
-    let base_dest = archive_dest_dir(executable_path, dest_base);
-    let mut dest_dir = base_dest.clone();
+/// `archive_dest_dir`, suffixed with -2, -3... if it already exists so runs never mix contents.
+fn unique_dest_dir(archive_path: &Path, dest_base: &Path) -> PathBuf {
+    let mut dest_dir = archive_dest_dir(archive_path, dest_base);
     if dest_dir.exists() {
-        let original_name = dest_dir
+        let orig_name = dest_dir
             .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("game")
+            .and_then(|s| s.to_str())
+            .unwrap_or("extracted")
             .to_string();
-        let mut index = 2usize;
+        let mut idx = 2usize;
         loop {
-            let candidate = dest_dir.with_file_name(format!("{original_name}-{index}"));
+            let candidate = dest_dir.with_file_name(format!("{orig_name}-{idx}"));
             if !candidate.exists() {
                 dest_dir = candidate;
                 break;
             }
-            index += 1;
+            idx += 1;
         }
     }
+    dest_dir
+}
 
-    fs::create_dir_all(&dest_dir).map_err(|e| format!("Create game dir failed: {e}"))?;
+fn install_standalone_executable(
+    executable_path: &Path,
+    dest_dir: &Path,
+    sd: &UnboundedSender<GameDownloadStatus>,
+) -> Result<(PathBuf, Option<PathBuf>), String> {
+    fs::create_dir_all(dest_dir).map_err(|e| format!("Create game dir failed: {e}"))?;
     let file_name = executable_path
         .file_name()
         .ok_or_else(|| "Executable has no file name".to_string())?;
@@ -369,47 +201,7 @@ fn install_standalone_executable(
         .map_err(|e| format!("Install standalone executable failed: {e}"))?;
     let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(1.0)));
 
-    Ok((dest_dir, Some(installed_path)))
-}
-
-fn extract_with_sevenz(
-    archive_path: &Path,
-    dest_base: &Path,
-) -> Result<(PathBuf, Option<PathBuf>), String> {
-    // Use archive_dest_dir and ensure unique directory to avoid mixing contents
-    let base_dest = archive_dest_dir(archive_path, dest_base);
-    let mut dest_dir = base_dest.clone();
-    if dest_dir.exists() {
-        let orig_name = dest_dir
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("extracted")
-            .to_string();
-        let mut idx = 2usize;
-        loop {
-            let candidate = dest_dir.with_file_name(format!("{orig_name}-{idx}"));
-            if !candidate.exists() {
-                dest_dir = candidate;
-                break;
-            }
-            idx += 1;
-        }
-    }
-
-    std::fs::create_dir_all(&dest_dir).map_err(|e| format!("Create dest dir failed: {e}"))?;
-    match sevenz_rust::decompress_file(archive_path, &dest_dir) {
-        Ok(()) => Ok((dest_dir.clone(), find_first_exe(&dest_dir))),
-        Err(e) => {
-            let msg = e.to_string();
-            if is_memory_alloc_failure(&msg) {
-                Err(format!(
-                    "7z decompress failed due to insufficient memory: {msg}"
-                ))
-            } else {
-                Err(format!("7z decompress (pure Rust) failed: {msg}"))
-            }
-        }
-    }
+    Ok((dest_dir.to_path_buf(), Some(installed_path)))
 }
 
 fn is_memory_alloc_failure(s: &str) -> bool {
@@ -432,24 +224,53 @@ pub fn extract_archive(
         .ok_or_else(|| "Archive has no file name".to_string())?;
 
     // Supported formats:
-    // - .zip (native streaming unzip)
-    // - .7z (pure Rust via sevenz_rust)
+    // - .zip (native, entries decompressed in parallel)
+    // - .7z (pure Rust via sevenz_rust, streamed under a memory limit)
     // - .rar (via unrar + UnRAR.dll on Windows)
     // - .exe (standalone executable; copied without launching)
-    if name_lower.ends_with(".exe") {
-        return install_standalone_executable(archive_path, dest_base, sd);
-    }
-    if name_lower.ends_with(".zip") {
-        return unzip_streaming(archive_path, dest_base, sd);
-    }
-    if name_lower.ends_with(".7z") {
-        return extract_with_sevenz(archive_path, dest_base);
+    let extract: fn(&Path, &Path, &UnboundedSender<GameDownloadStatus>) -> _ =
+        if name_lower.ends_with(".exe") {
+            install_standalone_executable
+        } else if name_lower.ends_with(".zip") {
+            |path, dest, sd| {
+                let (threads, names) = crate::app::settings::with_settings(|st| {
+                    (st.extract_threads, st.archive_name_encoding)
+                });
+                unzip::unzip_parallel(path, dest, sd, unzip::worker_count(threads), names)
+            }
+        } else if name_lower.ends_with(".7z") {
+            |path, dest, sd| {
+                let (limit, names) = crate::app::settings::with_settings(|st| {
+                    (st.sevenz_memory_limit_mb, st.archive_name_encoding)
+                });
+                sevenz::extract_with_sevenz(path, dest, sd, limit, names)
+            }
+        } else if name_lower.ends_with(".rar") {
+            |path, dest, _| {
+                let names = crate::app::settings::with_settings(|st| st.archive_name_encoding);
+                extract_with_unrar(path, dest, names)
+            }
+        } else {
+            return Err(format!("Unsupported archive format: {}", name_lower));
+        };
+
+    space::check_extract_space(archive_path, dest_base)?;
+    let dest_dir = unique_dest_dir(archive_path, dest_base);
+    let result = extract(archive_path, &dest_dir, sd);
+    if result.is_ok() {
+        // Record what was installed so "Verify files" can detect missing/changed files later
+        if let Err(e) = integrity::write_manifest(&dest_dir, archive_path) {
+            log::warn!("Failed to write manifest in {}: {}", dest_dir.display(), e);
+        }
     }
-    if name_lower.ends_with(".rar") {
-        return extract_with_unrar(archive_path, dest_base);
+    if let Err(e) = &result {
+        // Don't leave a half-extracted game behind (e.g. when the disk filled up)
+        log::warn!("Extraction into {} failed: {e}", dest_dir.display());
+        if let Err(e) = fs::remove_dir_all(&dest_dir) {
+            log::warn!("Failed to clean up {}: {}", dest_dir.display(), e);
+        }
     }
-
-    Err(format!("Unsupported archive format: {}", name_lower))
+    result
 }
 
 #[cfg(test)]
@@ -481,35 +302,27 @@ mod tests {
 
 fn extract_with_unrar(
     archive_path: &Path,
-    dest_base: &Path,
+    dest_dir: &Path,
+    name_encoding: ArchiveNameEncoding,
 ) -> Result<(PathBuf, Option<PathBuf>), String> {
-    // Use archive_dest_dir and ensure unique directory to avoid mixing contents
-    let base_dest = archive_dest_dir(archive_path, dest_base);
-    let mut dest_dir = base_dest.clone();
-    if dest_dir.exists() {
-        let orig_name = dest_dir
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("extracted")
-            .to_string();
-        let mut idx = 2usize;
-        loop {
-            let candidate = dest_dir.with_file_name(format!("{orig_name}-{idx}"));
-            if !candidate.exists() {
-                dest_dir = candidate;
-                break;
-            }
-            idx += 1;
-        }
-    }
-
-    std::fs::create_dir_all(&dest_dir).map_err(|e| format!("Create dest dir failed: {e}"))?;
+    std::fs::create_dir_all(dest_dir).map_err(|e| format!("Create dest dir failed: {e}"))?;
 
-    // Open for processing and extract every entry under dest_dir
     let rar_path = archive_path
         .to_str()
         .ok_or_else(|| "RAR path contains invalid UTF-8".to_string())?;
 
+    // List first: names are repaired with one encoding detected from the whole archive
+    let mut listed = Vec::new();
+    for header in unrar::Archive::new(rar_path)
+        .open_for_listing()
+        .map_err(|e| format!("UnRAR open failed: {e}"))?
+    {
+        let header = header.map_err(|e| format!("UnRAR read header failed: {e}"))?;
+        listed.push(header.filename.to_string_lossy().to_string());
+    }
+    let names = names::UnicodeNameFixer::new(listed.iter().map(String::as_str), name_encoding);
+
+    // Open for processing and extract every entry to its sanitised path under dest_dir
     let mut open = unrar::Archive::new(rar_path)
         .open_for_processing()
         .map_err(|e| format!("UnRAR open failed: {e}"))?;
@@ -517,15 +330,31 @@ fn extract_with_unrar(
     loop {
         match open.read_header() {
             Ok(Some(hdr)) => {
-                // Extract current entry into base directory (creates subdirs as needed)
-                open = hdr
-                    .extract_with_base(&dest_dir)
-                    .map_err(|e| format!("UnRAR extract failed: {e}"))?;
+                let name = hdr.entry().filename.to_string_lossy().to_string();
+                let out_path =
+                    sanitize_relative_path(&names.fix(&name), None).map(|rel| dest_dir.join(rel));
+                open = match out_path {
+                    Some(dir) if hdr.entry().is_directory() => {
+                        fs::create_dir_all(&dir)
+                            .map_err(|e| format!("Create dir {} failed: {e}", dir.display()))?;
+                        hdr.skip()
+                    }
+                    Some(path) => {
+                        if let Some(parent) = path.parent() {
+                            fs::create_dir_all(parent).map_err(|e| {
+                                format!("Create parent {} failed: {e}", parent.display())
+                            })?;
+                        }
+                        hdr.extract_to(&path)
+                    }
+                    None => hdr.skip(),
+                }
+                .map_err(|e| format!("UnRAR extract failed: {e}"))?;
             }
             Ok(None) => break,
             Err(e) => return Err(format!("UnRAR read header failed: {e}")),
         }
     }
 
-    Ok((dest_dir.clone(), find_first_exe(&dest_dir)))
+    Ok((dest_dir.to_path_buf(), find_first_exe(dest_dir)))
 }
@@ -0,0 +1,281 @@
+// Entry names that aren't UTF-8. Zip names without the UTF-8 flag are in whatever code page the
+// packer used (Shift-JIS and GBK for Japanese/Chinese games, CP1251 for Russian, CP437 by spec).
+// 7z and RAR store Unicode, but old packers wrote those bytes as Latin-1 characters, giving the
+// same mojibake. One encoding is detected per archive from all of its names, unless the user
+// picked one in Settings.
+
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, GBK, SHIFT_JIS, WINDOWS_1251, WINDOWS_1252};
+
+use crate::app::settings::store::ArchiveNameEncoding;
+
+/// Code page 437, bytes 0x80..=0xFF
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', //
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', //
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', //
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', //
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', //
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', //
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', //
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// Score of a name that isn't valid in the candidate encoding
+const INVALID_NAME: i64 = -5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum CodePage {
+    Cp437,
+    Other(&'static Encoding),
+}
+
+impl CodePage {
+    fn from_setting(setting: ArchiveNameEncoding) -> Option<Self> {
+        match setting {
+            ArchiveNameEncoding::Auto => None,
+            ArchiveNameEncoding::Cp437 => Some(CodePage::Cp437),
+            ArchiveNameEncoding::ShiftJis => Some(CodePage::Other(SHIFT_JIS)),
+            ArchiveNameEncoding::Gbk => Some(CodePage::Other(GBK)),
+            ArchiveNameEncoding::Cp1251 => Some(CodePage::Other(WINDOWS_1251)),
+        }
+    }
+
+    /// Decode a name; valid UTF-8 is always taken as UTF-8.
+    pub(super) fn decode(self, raw: &[u8]) -> String {
+        if let Ok(s) = std::str::from_utf8(raw) {
+            return s.to_string();
+        }
+        match self {
+            CodePage::Cp437 => decode_cp437(raw),
+            CodePage::Other(encoding) => encoding.decode_without_bom_handling(raw).0.into_owned(),
+        }
+    }
+
+    /// Like `decode`, but None when the bytes aren't valid in this code page.
+    fn decode_strict(self, raw: &[u8]) -> Option<String> {
+        match self {
+            CodePage::Other(encoding) if std::str::from_utf8(raw).is_err() => encoding
+                .decode_without_bom_handling_and_without_replacement(raw)
+                .map(Cow::into_owned),
+            _ => Some(self.decode(raw)),
+        }
+    }
+}
+
+/// Code page for the unflagged zip names `raw`; the valid UTF-8 ones need none, so only the
+/// rest are looked at (CP437 when nothing else fits).
+pub(super) fn zip_code_page<'a>(
+    raw: impl Iterator<Item = &'a [u8]>,
+    setting: ArchiveNameEncoding,
+) -> CodePage {
+    if let Some(forced) = CodePage::from_setting(setting) {
+        return forced;
+    }
+    let legacy: Vec<&[u8]> = raw.filter(|n| std::str::from_utf8(n).is_err()).collect();
+    detect(&legacy).unwrap_or(CodePage::Cp437)
+}
+
+pub(super) fn decode_cp437(raw: &[u8]) -> String {
+    raw.iter()
+        .map(|&b| {
+            if b < 0x80 {
+                b as char
+            } else {
+                CP437_HIGH[(b - 0x80) as usize]
+            }
+        })
+        .collect()
+}
+
+/// Repairs Unicode names (7z, RAR) that are really code page bytes stored one per character.
+pub(super) struct UnicodeNameFixer(Option<CodePage>);
+
+impl UnicodeNameFixer {
+    /// Decide from every name in the archive. Auto-detection only rewrites names when the bytes
+    /// clearly are UTF-8, Shift-JIS or GBK; a legitimate "Café" must stay as it is.
+    pub(super) fn new<'a>(
+        names: impl Iterator<Item = &'a str>,
+        setting: ArchiveNameEncoding,
+    ) -> Self {
+        let legacy: Vec<Vec<u8>> = names.filter_map(legacy_bytes).collect();
+        if legacy.is_empty() {
+            return Self(None);
+        }
+        if let Some(forced) = CodePage::from_setting(setting) {
+            return Self(Some(forced));
+        }
+        if legacy.iter().all(|n| std::str::from_utf8(n).is_ok()) {
+            // UTF-8 read as Latin-1; decode() takes the UTF-8 path
+            return Self(Some(CodePage::Cp437));
+        }
+        let refs: Vec<&[u8]> = legacy.iter().map(Vec::as_slice).collect();
+        match detect(&refs) {
+            Some(cp @ CodePage::Other(e)) if e == SHIFT_JIS || e == GBK => Self(Some(cp)),
+            _ => Self(None),
+        }
+    }
+
+    pub(super) fn fix<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        self.0
+            .zip(legacy_bytes(name))
+            .and_then(|(cp, raw)| cp.decode_strict(&raw))
+            .map_or(Cow::Borrowed(name), Cow::Owned)
+    }
+}
+
+/// The original bytes of a name whose characters are all single bytes read as Latin-1 or
+/// Windows-1252; None for ASCII and for real Unicode names.
+fn legacy_bytes(name: &str) -> Option<Vec<u8>> {
+    if name.is_ascii() {
+        return None;
+    }
+    let mut out = Vec::with_capacity(name.len());
+    let mut buf = [0u8; 4];
+    for c in name.chars() {
+        if (c as u32) <= 0xFF {
+            out.push(c as u32 as u8);
+            continue;
+        }
+        // "€", "‚", "ƒ"... are how Windows-1252 shows bytes 0x80..=0x9F
+        let (bytes, _, unmappable) = WINDOWS_1252.encode(c.encode_utf8(&mut buf));
+        match bytes.as_ref() {
+            [b] if !unmappable && (0x80..=0x9F).contains(b) => out.push(*b),
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+/// Guess the code page of names that are not UTF-8. None when nothing matches well.
+fn detect(names: &[&[u8]]) -> Option<CodePage> {
+    if names.is_empty() {
+        return None;
+    }
+    if looks_cyrillic(&names.join(&b'/')) {
+        return Some(CodePage::Other(WINDOWS_1251));
+    }
+    // Scored per name so one odd name doesn't rule an encoding out
+    let mut ja = 0;
+    let mut zh = 0;
+    for name in names {
+        let ja_name = SHIFT_JIS
+            .decode_without_bom_handling_and_without_replacement(name)
+            .map_or(INVALID_NAME, |text| japanese_score(&text));
+        let zh_name = GBK
+            .decode_without_bom_handling_and_without_replacement(name)
+            .map_or(INVALID_NAME, |_| chinese_score(name));
+        ja += ja_name;
+        zh += zh_name;
+    }
+    if ja > 0 && ja >= zh {
+        Some(CodePage::Other(SHIFT_JIS))
+    } else if zh > 0 {
+        Some(CodePage::Other(GBK))
+    } else {
+        None
+    }
+}
+
+/// Kana make Japanese unmistakable; half-width katakana is what GBK bytes turn into.
+fn japanese_score(text: &str) -> i64 {
+    text.chars()
+        .map(|c| match c {
+            '\u{3040}'..='\u{30FF}' => 2,
+            '\u{4E00}'..='\u{9FFF}' => 1,
+            '\u{FF61}'..='\u{FF9F}' => -3,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Pairs in the GB2312 hanzi block (common characters) versus GBK extension pairs, which is
+/// what Shift-JIS kana and kanji bytes fall into.
+fn chinese_score(data: &[u8]) -> i64 {
+    let mut score = 0;
+    let mut i = 0;
+    while i < data.len() {
+        let lead = data[i];
+        if lead < 0x80 {
+            i += 1;
+            continue;
+        }
+        let trail = data.get(i + 1).copied().unwrap_or(0);
+        if (0xB0..=0xF7).contains(&lead) && (0xA1..=0xFE).contains(&trail) {
+            score += 1;
+        } else {
+            score -= 2;
+        }
+        i += 2;
+    }
+    score
+}
+
+/// CP1251 text: almost every high byte is a Cyrillic letter and case only changes at word
+/// starts. CJK bytes are letters in CP1251 too, but with symbols and random case in between.
+fn looks_cyrillic(data: &[u8]) -> bool {
+    let is_upper = |b: u8| (0xC0..=0xDF).contains(&b) || b == 0xA8;
+    let is_lower = |b: u8| b >= 0xE0 || b == 0xB8;
+    let high = data.iter().filter(|&&b| b >= 0x80).count();
+    let letters = data.iter().filter(|&&b| is_upper(b) || is_lower(b)).count();
+    let case_breaks = data
+        .windows(2)
+        .filter(|w| is_lower(w[0]) && is_upper(w[1]))
+        .count();
+    high > 0 && letters * 100 >= high * 95 && case_breaks * 10 <= letters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(encoding: &'static Encoding, s: &str) -> Vec<u8> {
+        encoding.encode(s).0.into_owned()
+    }
+
+    #[test]
+    fn test_detects_zip_name_code_pages() {
+        let cases: [(&'static Encoding, &[&str]); 3] = [
+            (SHIFT_JIS, &["ゲーム/画像/背景.png", "ゲーム/起動.exe"]),
+            (GBK, &["游戏/图片/背景.png", "游戏/启动.exe"]),
+            (WINDOWS_1251, &["Игра/картинки/Фон.png", "Игра/Запуск.exe"]),
+        ];
+        for (encoding, names) in cases {
+            let raw: Vec<Vec<u8>> = names.iter().map(|n| encode(encoding, n)).collect();
+            let cp = zip_code_page(raw.iter().map(Vec::as_slice), ArchiveNameEncoding::Auto);
+            assert_eq!(cp, CodePage::Other(encoding), "{names:?}");
+            assert_eq!(cp.decode(&raw[0]), names[0]);
+        }
+
+        // Western names from DOS-era packers and plain UTF-8
+        let cp = zip_code_page(
+            [&b"Caf\x82/readme.txt"[..]].into_iter(),
+            ArchiveNameEncoding::Auto,
+        );
+        assert_eq!(cp, CodePage::Cp437);
+        assert_eq!(cp.decode(b"Caf\x82"), "Café");
+        assert_eq!(cp.decode("日本語".as_bytes()), "日本語");
+
+        // The user's choice wins over detection
+        let raw = encode(GBK, "游戏");
+        let cp = zip_code_page([raw.as_slice()].into_iter(), ArchiveNameEncoding::Cp1251);
+        assert_eq!(cp, CodePage::Other(WINDOWS_1251));
+    }
+
+    #[test]
+    fn test_fixes_latin1_mojibake_in_unicode_names() {
+        let sjis = encode(SHIFT_JIS, "ゲーム/起動.exe");
+        let mojibake: String = sjis.iter().map(|&b| b as char).collect();
+        let names = [mojibake.as_str(), "Café/readme.txt", "data/ok.txt"];
+        let fixer = UnicodeNameFixer::new(names.into_iter(), ArchiveNameEncoding::Auto);
+        assert_eq!(fixer.fix(&mojibake), "ゲーム/起動.exe");
+        assert_eq!(fixer.fix("data/ok.txt"), "data/ok.txt");
+
+        let fixer =
+            UnicodeNameFixer::new(["Café/readme.txt"].into_iter(), ArchiveNameEncoding::Auto);
+        assert_eq!(fixer.fix("Café/readme.txt"), "Café/readme.txt");
+        let fixer = UnicodeNameFixer::new(["ゲーム.exe"].into_iter(), ArchiveNameEncoding::Auto);
+        assert_eq!(fixer.fix("ゲーム.exe"), "ゲーム.exe");
+    }
+}
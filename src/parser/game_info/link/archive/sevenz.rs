@@ -0,0 +1,189 @@
+// Streaming 7z extraction. A solid block is decoded as one stream, so the decoder's dictionary
+// is what uses memory, not the archive size. It is estimated from the coder properties in the
+// header and checked against the configured ceiling before anything is decompressed; entries
+// are then written one by one through a fixed buffer.
+
+use std::fs::{self, File as StdFile};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use sevenz_rust::{Archive, Password, SevenZMethod, SevenZReader};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::names::UnicodeNameFixer;
+use super::{find_first_exe, is_memory_alloc_failure, sanitize_relative_path};
+use crate::app::settings::store::ArchiveNameEncoding;
+use crate::game_download::{GameDownloadStatus, Progress};
+use crate::parser::game_info::link::format_size;
+
+/// How often progress is reported
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+pub(super) fn extract_with_sevenz(
+    archive_path: &Path,
+    dest_dir: &Path,
+    sd: &UnboundedSender<GameDownloadStatus>,
+    memory_limit_mb: u32,
+    name_encoding: ArchiveNameEncoding,
+) -> Result<(PathBuf, Option<PathBuf>), String> {
+    let mut reader = SevenZReader::open(archive_path, Password::empty())
+        .map_err(|e| format!("7z open failed: {e}"))?;
+
+    let needed = decoder_memory(reader.archive())?;
+    let limit = u64::from(memory_limit_mb) * 1024 * 1024;
+    if limit > 0 && needed > limit {
+        return Err(format!(
+            "7z archive needs about {} of memory to decompress (dictionary size), above the {} \
+             limit. Raise \"7z memory limit\" in Settings or extract it with 7-Zip.",
+            format_size(needed),
+            format_size(limit)
+        ));
+    }
+
+    fs::create_dir_all(dest_dir).map_err(|e| format!("Create dest dir failed: {e}"))?;
+    let total_bytes: u64 = reader
+        .archive()
+        .files
+        .iter()
+        .filter(|f| !f.is_directory())
+        .map(|f| f.size())
+        .sum();
+    let names = UnicodeNameFixer::new(
+        reader.archive().files.iter().map(|f| f.name()),
+        name_encoding,
+    );
+    let mut extracted = 0u64;
+    let mut last_report = Instant::now();
+    let mut buf = vec![0u8; 64 * 1024];
+
+    let result = reader.for_each_entries(|entry, data| {
+        let Some(rel) = sanitize_relative_path(&names.fix(entry.name()), None) else {
+            // Still decode it: later entries of a solid block come after it in the stream
+            extracted +=
+                std::io::copy(data, &mut std::io::sink()).map_err(sevenz_rust::Error::io)?;
+            return Ok(true);
+        };
+        let out_path = dest_dir.join(rel);
+        if entry.is_directory() {
+            fs::create_dir_all(&out_path).map_err(sevenz_rust::Error::io)?;
+            return Ok(true);
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(sevenz_rust::Error::io)?;
+        }
+        let mut out_file = StdFile::create(&out_path).map_err(|e| {
+            sevenz_rust::Error::other(format!("Create {} failed: {e}", out_path.display()))
+        })?;
+        loop {
+            let n = data.read(&mut buf).map_err(sevenz_rust::Error::io)?;
+            if n == 0 {
+                break;
+            }
+            out_file.write_all(&buf[..n]).map_err(|e| {
+                sevenz_rust::Error::other(format!("Write {} failed: {e}", out_path.display()))
+            })?;
+            extracted += n as u64;
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                last_report = Instant::now();
+                let progress = extracted as f32 / total_bytes.max(1) as f32;
+                let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(progress)));
+            }
+        }
+        Ok(true)
+    });
+
+    if let Err(e) = result {
+        let msg = e.to_string();
+        return Err(if is_memory_alloc_failure(&msg) {
+            format!("7z decompress failed due to insufficient memory: {msg}")
+        } else {
+            format!("7z decompress failed: {msg}")
+        });
+    }
+
+    // Ensure final 100% notification
+    let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(1.0)));
+
+    Ok((dest_dir.to_path_buf(), find_first_exe(dest_dir)))
+}
+
+/// Peak decoder memory in bytes. Blocks are decoded one after another, so this is the largest
+/// block's coder chain.
+fn decoder_memory(archive: &Archive) -> Result<u64, String> {
+    let mut peak = 0u64;
+    for folder in &archive.folders {
+        let mut kib = 0u64;
+        for coder in &folder.coders {
+            let props = &coder.properties;
+            let method = coder.decompression_method_id();
+            if method == SevenZMethod::ID_LZMA2 {
+                let dict = props
+                    .first()
+                    .ok_or("7z header: LZMA2 properties too short")
+                    .and_then(|&bits| lzma2_dict_size(bits))?;
+                kib += u64::from(sevenz_rust::lzma::lzma2_get_memery_usage(dict));
+            } else if method == SevenZMethod::ID_LZMA {
+                if props.len() < 5 {
+                    return Err("7z header: LZMA properties too short".to_string());
+                }
+                let dict = u32::from_le_bytes([props[1], props[2], props[3], props[4]]);
+                let usage = sevenz_rust::lzma::lzma_get_memery_usage_by_props(dict, props[0])
+                    .map_err(|e| format!("7z header: {e}"))?;
+                kib += u64::from(usage);
+            }
+            // Filters, zstd, bzip2 and copy need a few MiB at most
+        }
+        peak = peak.max(kib * 1024);
+    }
+    Ok(peak)
+}
+
+/// LZMA2 dictionary size from its one-byte property (same encoding as xz).
+fn lzma2_dict_size(bits: u8) -> Result<u32, &'static str> {
+    match bits {
+        0..=39 => Ok((2 | (u32::from(bits) & 1)) << (bits / 2 + 11)),
+        40 => Ok(u32::MAX),
+        _ => Err("7z header: unsupported LZMA2 dictionary size"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[test]
+    fn test_sevenz_streams_entries_and_refuses_above_memory_limit() {
+        let root = std::env::temp_dir().join(format!("f95-sevenz-test-{}", uuid::Uuid::new_v4()));
+        let src = root.join("src");
+        fs::create_dir_all(src.join("data")).unwrap();
+        fs::write(src.join("Game.exe"), b"exe").unwrap();
+        fs::write(src.join("data/level1.dat"), vec![7u8; 200_000]).unwrap();
+        let archive = root.join("Game-1.0.7z");
+        sevenz_rust::compress_to_path(&src, &archive).unwrap();
+        let dest = root.join("games").join("game-1.0");
+        let (sender, _receiver) = unbounded_channel();
+
+        // The default encoder uses an 8 MiB dictionary
+        let err = extract_with_sevenz(&archive, &dest, &sender, 1, ArchiveNameEncoding::Auto)
+            .unwrap_err();
+        assert!(err.contains("memory"), "{err}");
+        assert!(!dest.exists());
+
+        let (dir, exe) =
+            extract_with_sevenz(&archive, &dest, &sender, 0, ArchiveNameEncoding::Auto).unwrap();
+        assert_eq!(dir, dest);
+        assert_eq!(exe, Some(dest.join("Game.exe")));
+        assert_eq!(
+            fs::read(dest.join("data/level1.dat")).unwrap().len(),
+            200_000
+        );
+
+        assert_eq!(lzma2_dict_size(16).unwrap(), 1 << 20);
+        assert_eq!(lzma2_dict_size(17).unwrap(), 3 << 19);
+        assert!(lzma2_dict_size(41).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,369 @@
+// Parallel zip extraction. Output paths are planned once up front (sanitisation, single
+// top-level folder stripping, case-insensitive collision renames), then workers from a
+// threadpool decompress entries through their own archive handles while the calling thread
+// aggregates progress.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fs::{self, File as StdFile};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use threadpool::ThreadPool;
+use tokio::sync::mpsc::UnboundedSender;
+use zip::ZipArchive;
+
+use super::names;
+use super::{find_first_exe, sanitize_relative_path};
+use crate::app::settings::store::ArchiveNameEncoding;
+use crate::game_download::{GameDownloadStatus, Progress};
+
+/// Upper bound for automatic parallelism; past this the disk is the bottleneck anyway
+const MAX_AUTO_THREADS: usize = 8;
+/// How often aggregated progress is reported
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+struct PlannedFile {
+    index: usize,
+    out_path: PathBuf,
+    size: u64,
+}
+
+/// Worker count for `threads` from settings (0 = automatic).
+pub(super) fn worker_count(threads: u8) -> usize {
+    if threads > 0 {
+        return threads as usize;
+    }
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(MAX_AUTO_THREADS)
+}
+
+pub(super) fn unzip_parallel(
+    zip_path: &Path,
+    dest_dir: &Path,
+    sd: &UnboundedSender<GameDownloadStatus>,
+    threads: usize,
+    name_encoding: ArchiveNameEncoding,
+) -> Result<(PathBuf, Option<PathBuf>), String> {
+    let file = StdFile::open(zip_path).map_err(|e| format!("Open zip failed: {e}"))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Read zip failed: {e}"))?;
+
+    fs::create_dir_all(dest_dir).map_err(|e| format!("Create dest dir failed: {e}"))?;
+    let mut plan = plan_entries(&mut archive, dest_dir, name_encoding)?;
+    let total_bytes: u64 = plan.iter().map(|p| p.size).sum();
+
+    // Largest entries first so one big asset doesn't finish alone at the end
+    plan.sort_by_key(|p| Reverse(p.size));
+    let plan = Arc::new(plan);
+    let next = Arc::new(AtomicUsize::new(0));
+    let extracted = Arc::new(AtomicU64::new(0));
+    let abort = Arc::new(AtomicBool::new(false));
+    let first_error: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    let workers = threads.clamp(1, plan.len().max(1));
+    let pool = ThreadPool::new(workers);
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    for _ in 0..workers {
+        let zip_path = zip_path.to_path_buf();
+        let plan = plan.clone();
+        let next = next.clone();
+        let extracted = extracted.clone();
+        let abort = abort.clone();
+        let first_error = first_error.clone();
+        let done_tx = done_tx.clone();
+        pool.execute(move || {
+            if let Err(e) = extract_worker(&zip_path, &plan, &next, &extracted, &abort) {
+                abort.store(true, Ordering::Relaxed);
+                first_error.lock().unwrap().get_or_insert(e);
+            }
+            let _ = done_tx.send(());
+        });
+    }
+    drop(done_tx);
+
+    let mut finished = 0;
+    while finished < workers {
+        match done_rx.recv_timeout(PROGRESS_INTERVAL) {
+            Ok(()) => finished += 1,
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            // A worker panicked; the pool dropped its sender
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        let progress = if total_bytes == 0 {
+            1.0
+        } else {
+            extracted.load(Ordering::Relaxed) as f32 / total_bytes as f32
+        };
+        let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(progress)));
+    }
+    pool.join();
+    if pool.panic_count() > 0 {
+        return Err("Unzip worker panicked".to_string());
+    }
+    if let Some(e) = first_error.lock().unwrap().take() {
+        return Err(e);
+    }
+
+    // Ensure final 100% notification
+    let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(1.0)));
+
+    Ok((dest_dir.to_path_buf(), find_first_exe(dest_dir)))
+}
+
+/// Decide every output path once and create the directory tree.
+fn plan_entries<R: Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    dest_dir: &Path,
+    name_encoding: ArchiveNameEncoding,
+) -> Result<Vec<PlannedFile>, String> {
+    let entries: Vec<(Vec<u8>, String, bool, u64)> = (0..archive.len())
+        .map(|i| {
+            let f = archive
+                .by_index_raw(i)
+                .map_err(|e| format!("Zip idx {i} err: {e}"))?;
+            Ok((
+                f.name_raw().to_vec(),
+                f.name().to_string(),
+                f.is_dir(),
+                f.size(),
+            ))
+        })
+        .collect::<Result<_, String>>()?;
+
+    // Without the UTF-8 flag the zip crate reads names as CP437; decode them ourselves unless
+    // a Unicode path extra field already gave the real name. Unflagged names are often UTF-8
+    // anyway (macOS Archive Utility); decode() takes those as UTF-8.
+    let legacy: Vec<bool> = entries
+        .iter()
+        .map(|(raw, name, ..)| !raw.is_ascii() && *name == names::decode_cp437(raw))
+        .collect();
+    let code_page = names::zip_code_page(
+        entries
+            .iter()
+            .zip(&legacy)
+            .filter(|(_, legacy)| **legacy)
+            .map(|((raw, ..), _)| raw.as_slice()),
+        name_encoding,
+    );
+    let names: Vec<(String, bool, u64)> = entries
+        .into_iter()
+        .zip(legacy)
+        .map(|((raw, name, is_dir, size), legacy)| {
+            let name = if legacy { code_page.decode(&raw) } else { name };
+            (name, is_dir, size)
+        })
+        .collect();
+
+    let strip_prefix = single_top_level(names.iter().map(|(n, ..)| n.as_str()));
+
+    let mut plan = Vec::new();
+    // Track case-insensitive created file paths to avoid collisions on Windows
+    let mut used_rel_lower: HashSet<String> = HashSet::new();
+    for (index, (name, is_dir, size)) in names.into_iter().enumerate() {
+        let rel = match sanitize_relative_path(&name, strip_prefix.as_deref()) {
+            Some(p) => p,
+            None => continue,
+        };
+        if is_dir {
+            let dir = dest_dir.join(&rel);
+            if let Err(e) = fs::create_dir_all(&dir) {
+                log::warn!("Create dir {} failed: {}", dir.display(), e);
+            }
+            continue;
+        }
+        let rel = avoid_case_collision(rel, &mut used_rel_lower);
+        let out_path = dest_dir.join(&rel);
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Create parent {} failed: {}", parent.display(), e))?;
+        }
+        plan.push(PlannedFile {
+            index,
+            out_path,
+            size,
+        });
+    }
+    Ok(plan)
+}
+
+/// "Game/" when every entry lives under one top-level folder (it is stripped on extraction).
+fn single_top_level<'a>(names: impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut top_levels: HashSet<String> = HashSet::new();
+    let mut root_files = false;
+    for name in names {
+        let n = name.replace('\\', "/");
+        if let Some(pos) = n.find('/') {
+            let top = &n[..pos];
+            if !top.is_empty() {
+                top_levels.insert(top.to_string());
+            }
+        } else {
+            root_files = true;
+            if !n.is_empty() {
+                top_levels.insert(n.clone());
+            }
+        }
+    }
+    if !root_files && top_levels.len() == 1 {
+        Some(format!("{}/", top_levels.iter().next().unwrap()))
+    } else {
+        None
+    }
+}
+
+/// Append (2), (3)... before the extension when the path only differs by case from an
+/// earlier one.
+fn avoid_case_collision(rel: PathBuf, used_rel_lower: &mut HashSet<String>) -> PathBuf {
+    let rel_key = rel.to_string_lossy().to_ascii_lowercase();
+    if used_rel_lower.insert(rel_key) {
+        return rel;
+    }
+    let file_name = rel.file_name().and_then(|s| s.to_str()).unwrap_or("file");
+    let (stem, ext_opt) = match file_name.rsplit_once('.') {
+        Some((st, ex)) if !st.is_empty() => (st.to_string(), Some(ex.to_string())),
+        _ => (file_name.to_string(), None),
+    };
+    let mut n = 2usize;
+    loop {
+        let mut new_name = format!("{stem} ({n})");
+        if let Some(ex) = &ext_opt {
+            new_name.push('.');
+            new_name.push_str(ex);
+        }
+        let candidate_rel = rel.with_file_name(new_name);
+        let candidate_key = candidate_rel.to_string_lossy().to_ascii_lowercase();
+        if used_rel_lower.insert(candidate_key) {
+            return candidate_rel;
+        }
+        n += 1;
+    }
+}
+
+/// Take planned entries off the shared queue until it is empty or another worker failed.
+fn extract_worker(
+    zip_path: &Path,
+    plan: &[PlannedFile],
+    next: &AtomicUsize,
+    extracted: &AtomicU64,
+    abort: &AtomicBool,
+) -> Result<(), String> {
+    let file = StdFile::open(zip_path).map_err(|e| format!("Open zip failed: {e}"))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Read zip failed: {e}"))?;
+    let mut buf = vec![0u8; 64 * 1024];
+
+    while !abort.load(Ordering::Relaxed) {
+        let Some(entry) = plan.get(next.fetch_add(1, Ordering::Relaxed)) else {
+            break;
+        };
+        let mut f = archive
+            .by_index(entry.index)
+            .map_err(|e| format!("Zip idx {} err: {e}", entry.index))?;
+        let mut out_file = StdFile::create(&entry.out_path)
+            .map_err(|e| format!("Create {} failed: {}", entry.out_path.display(), e))?;
+        loop {
+            match f.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    out_file
+                        .write_all(&buf[..n])
+                        .map_err(|e| format!("Write {} failed: {}", entry.out_path.display(), e))?;
+                    extracted.fetch_add(n as u64, Ordering::Relaxed);
+                }
+                Err(e) => return Err(format!("Read entry {} failed: {}", f.name(), e)),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_unzip_parallel_strips_top_folder_and_renames_case_collisions() {
+        let root = std::env::temp_dir().join(format!("f95-unzip-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let zip_path = root.join("Game-1.0.zip");
+        {
+            let mut w = zip::ZipWriter::new(StdFile::create(&zip_path).unwrap());
+            let opts = SimpleFileOptions::default();
+            w.add_directory("Game/", opts).unwrap();
+            for i in 0..20 {
+                w.start_file(format!("Game/data/file{i}.txt"), opts)
+                    .unwrap();
+                w.write_all(format!("content {i}").as_bytes()).unwrap();
+            }
+            w.start_file("Game/readme.txt", opts).unwrap();
+            w.write_all(b"lower").unwrap();
+            w.start_file("Game/README.txt", opts).unwrap();
+            w.write_all(b"upper").unwrap();
+            w.finish().unwrap();
+        }
+        let dest = root.join("games").join("game-1.0");
+        let (sender, _receiver) = unbounded_channel();
+
+        let (dir, _) =
+            unzip_parallel(&zip_path, &dest, &sender, 4, ArchiveNameEncoding::Auto).unwrap();
+
+        assert_eq!(dir, dest);
+        assert_eq!(
+            fs::read_to_string(dest.join("data/file7.txt")).unwrap(),
+            "content 7"
+        );
+        assert_eq!(
+            fs::read_to_string(dest.join("readme.txt")).unwrap(),
+            "lower"
+        );
+        assert_eq!(
+            fs::read_to_string(dest.join("README (2).txt")).unwrap(),
+            "upper"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_unzip_decodes_unflagged_utf8_names() {
+        let root = std::env::temp_dir().join(format!("f95-unzip-utf8-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let zip_path = root.join("Game.zip");
+        let real = "ゲーム/起動.txt";
+        // An ASCII name of the same length keeps the UTF-8 flag off; the bytes are swapped after
+        let placeholder = "x".repeat(real.len());
+        let mut bytes = Vec::new();
+        {
+            let mut w = zip::ZipWriter::new(std::io::Cursor::new(&mut bytes));
+            w.start_file(placeholder.as_str(), SimpleFileOptions::default())
+                .unwrap();
+            w.write_all(b"start").unwrap();
+            w.finish().unwrap();
+        }
+        let mut i = 0;
+        while let Some(at) = bytes[i..]
+            .windows(placeholder.len())
+            .position(|w| w == placeholder.as_bytes())
+        {
+            let at = i + at;
+            bytes[at..at + real.len()].copy_from_slice(real.as_bytes());
+            i = at + real.len();
+        }
+        fs::write(&zip_path, bytes).unwrap();
+        let dest = root.join("games").join("game");
+        let (sender, _receiver) = unbounded_channel();
+
+        unzip_parallel(&zip_path, &dest, &sender, 1, ArchiveNameEncoding::Auto).unwrap();
+
+        // The single top folder is stripped
+        assert_eq!(fs::read_to_string(dest.join("起動.txt")).unwrap(), "start");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,46 +1,77 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use reqwest::Response;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::app::settings::APP_SETTINGS;
-use crate::game_download::{GameDownloadStatus, Progress};
+use crate::game_download::throttle::DownloadThrottle;
+use crate::game_download::{GameDownloadStatus, Progress, archive_store};
 
 use super::archive::extract_archive;
+use super::space::is_out_of_space;
 
+/// Stream the response to `file` in the background, then extract it. Without a content length
+/// the body is read to EOF and progress stays indeterminate (reported with `total: 0`).
 pub async fn start_download_task(
     mut resp: Response,
     sd: UnboundedSender<GameDownloadStatus>,
     mut file: tokio::fs::File,
     filepath: PathBuf,
-) -> bool {
-    let total_size = match resp.content_length() {
-        Some(sz) => sz,
-        None => {
-            log::warn!("server didn't send content length");
+    prefix: Vec<u8>,
+    content_length: Option<u64>,
+    throttle: Arc<DownloadThrottle>,
+) {
+    let total_size = content_length.unwrap_or_else(|| {
+        log::info!("server didn't send content length, downloading until EOF");
+        let _ = sd.send(GameDownloadStatus::Downloading(Progress::Unknown));
+        0
+    });
+
+    tokio::spawn(async move {
+        // Bytes already read while validating the response
+        let mut downloaded = prefix.len() as u64;
+        if let Err(e) = file.write_all(&prefix).await {
+            log::info!("write error: {:?}", e);
             let _ = sd.send(GameDownloadStatus::Downloading(Progress::Error(
-                "Server didn't send content length".to_string(),
+                "Couldn't write data to disk".to_string(),
             )));
-            return false;
+            drop(file);
+            remove_partial(&filepath);
+            return;
         }
-    };
-
-    tokio::spawn(async move {
-        let mut downloaded = 0u64;
         loop {
             match resp.chunk().await {
                 Ok(Some(bytes)) => {
                     if let Err(e) = file.write_all(&bytes).await {
                         log::info!("write error: {:?}", e);
-                        let _ = sd.send(GameDownloadStatus::Downloading(Progress::Error(
-                            "Couldn't write data to disk".to_string(),
-                        )));
+                        let msg = if is_out_of_space(&e) {
+                            format!("Disk full while downloading to {}", filepath.display())
+                        } else {
+                            "Couldn't write data to disk".to_string()
+                        };
+                        let _ = sd.send(GameDownloadStatus::Downloading(Progress::Error(msg)));
+                        drop(file);
+                        remove_partial(&filepath);
                         break;
                     }
                     downloaded += bytes.len() as u64;
-                    let progress = (downloaded as f32) / (total_size as f32);
-                    let _ = sd.send(GameDownloadStatus::Downloading(Progress::Pending(progress)));
+                    throttle.consume(bytes.len() as u64, &sd).await;
+                    let transferred = GameDownloadStatus::Transferred {
+                        done: downloaded,
+                        total: total_size,
+                    };
+                    if sd.send(transferred).is_err() {
+                        // Nobody listens any more: the download was cancelled
+                        log::info!("download cancelled, removing {}", filepath.display());
+                        drop(file);
+                        remove_partial(&filepath);
+                        break;
+                    }
                 }
                 Ok(None) => {
                     log::info!("download completed");
@@ -49,53 +80,17 @@ pub async fn start_download_task(
                     }
                     // Close the file handle before extraction
                     drop(file);
-
-                    let archive_path = filepath.clone();
-                    let sd_unzip = sd.clone();
-                    let dest_base = {
-                        let s = APP_SETTINGS.read().unwrap();
-                        s.extract_dir.clone()
-                    };
-
-                    // Notify that extraction started
-                    let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(0.0)));
-
-                    // Run potentially heavy extraction on a blocking thread
-                    let path = archive_path.clone();
-                    let unzip_res = tokio::task::spawn_blocking(move || {
-                        extract_archive(&path, &dest_base, &sd_unzip)
-                    })
-                    .await;
-
-                    match unzip_res {
-                        Ok(Ok((dest_dir, exe_path))) => {
-                            // Delete the original archive after successful extraction
-                            if let Err(e) = fs::remove_file(&archive_path) {
-                                log::warn!(
-                                    "Failed to delete archive {}: {}",
-                                    archive_path.display(),
-                                    e
-                                );
-                            }
-                            let _ = sd.send(GameDownloadStatus::Completed { dest_dir, exe_path });
-                        }
-                        Ok(Err(msg)) => {
-                            let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Error(msg)));
-                        }
-                        Err(e) => {
-                            let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Error(
-                                format!("Unzip task join error: {e}"),
-                            )));
-                        }
-                    }
-                    log::info!("successfully extracted");
+                    extract_downloaded(&sd, filepath).await;
                     break;
                 }
                 Err(e) => {
                     log::error!("read chunk error: {:?}", e);
+                    let _ = sd.send(GameDownloadStatus::HostingFailed);
                     let _ = sd.send(GameDownloadStatus::Downloading(Progress::Error(format!(
                         "Error reading chunk: {e}"
                     ))));
+                    drop(file);
+                    remove_partial(&filepath);
                     break;
                 }
             }
@@ -103,6 +98,50 @@ pub async fn start_download_task(
     });
 
     tokio::task::yield_now().await;
+}
+
+/// Extract a finished download into the Extract-to folder and report completion.
+pub(super) async fn extract_downloaded(sd: &UnboundedSender<GameDownloadStatus>, filepath: PathBuf) {
+    let archive_path = filepath.clone();
+    let sd_unzip = sd.clone();
+    let dest_base = {
+        let s = APP_SETTINGS.read().unwrap();
+        s.extract_dir.clone()
+    };
+
+    // Notify that extraction started
+    let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Pending(0.0)));
+
+    // Run potentially heavy extraction on a blocking thread
+    let unzip_res =
+        tokio::task::spawn_blocking(move || extract_archive(&filepath, &dest_base, &sd_unzip))
+            .await;
+
+    match unzip_res {
+        Ok(Ok((dest_dir, exe_path))) => {
+            // Delete the original archive (or hand it to the archive library)
+            let archive = archive_store::keep_or_delete(&archive_path);
+            let _ = sd.send(GameDownloadStatus::Completed {
+                dest_dir,
+                exe_path,
+                archive,
+            });
+            log::info!("successfully extracted");
+        }
+        Ok(Err(msg)) => {
+            let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Error(msg)));
+        }
+        Err(e) => {
+            let _ = sd.send(GameDownloadStatus::Unzipping(Progress::Error(format!(
+                "Unzip task join error: {e}"
+            ))));
+        }
+    }
+}
 
-    true
+/// Remove an incomplete download so it doesn't keep occupying the temp volume.
+pub(super) fn remove_partial(filepath: &Path) {
+    if let Err(e) = fs::remove_file(filepath) {
+        log::warn!("Failed to remove partial {}: {}", filepath.display(), e);
+    }
 }
@@ -36,13 +36,13 @@ pub async fn resolve_gofile_file(
 
     // Gofile replaced the old `wt` query parameter with a time-limited
     // X-Website-Token derived from the account token and request headers.
-    let website_token = website_token(&token, SystemTime::now());
+    let website_token = website_token(&token, &crate::net::user_agent(), SystemTime::now());
     let url = format!(
         "https://api.gofile.io/contents/{id}?contentFilter=&page=1&pageSize=1000&sortField=name&sortDirection=1"
     );
 
     // Query folder contents with Authorization
-    let resp = crate::net::client()
+    let resp = crate::net::hosting_client()
         .get(url)
         .header("authorization", format!("Bearer {token}"))
         .header("x-website-token", website_token)
@@ -79,7 +79,7 @@ pub async fn resolve_gofile_file(
 async fn account_token() -> Result<&'static str, GofileLinkError> {
     ACCOUNT_TOKEN
         .get_or_try_init(|| async {
-            let response = crate::net::client()
+            let response = crate::net::hosting_client()
                 .post("https://api.gofile.io/accounts")
                 .send()
                 .await
@@ -96,13 +96,10 @@ async fn account_token() -> Result<&'static str, GofileLinkError> {
         .map(String::as_str)
 }
 
-fn website_token(account_token: &str, now: SystemTime) -> String {
+fn website_token(account_token: &str, user_agent: &str, now: SystemTime) -> String {
     let time_bucket =
         now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() / WEBSITE_TOKEN_PERIOD_SECS;
-    let input = format!(
-        "{}::::{account_token}::{time_bucket}::{WEBSITE_TOKEN_SECRET}",
-        crate::net::USER_AGENT
-    );
+    let input = format!("{user_agent}::::{account_token}::{time_bucket}::{WEBSITE_TOKEN_SECRET}");
     format!("{:x}", Sha256::digest(input.as_bytes()))
 }
 
@@ -203,7 +200,7 @@ mod tests {
         let now = UNIX_EPOCH + Duration::from_secs(123_983 * WEBSITE_TOKEN_PERIOD_SECS);
 
         assert_eq!(
-            website_token("test-token", now),
+            website_token("test-token", crate::net::USER_AGENT, now),
             "a26ce316e244cf605cf24c3fc80e999372c365b96742e29155b87765da234021"
         );
     }
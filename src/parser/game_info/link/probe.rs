@@ -0,0 +1,152 @@
+// Link health check: resolve a download link like a real download would, but only read the
+// response headers and first bytes (or MEGA node metadata) to report reachability, file size and
+// name. A hosting's HTML error page counts as dead, as it would fail the real download.
+
+use reqwest::header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE, RANGE};
+
+use super::info::DirectRequest;
+use super::validate::validate_response;
+use super::{DownloadError, DownloadLink};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbeStatus {
+    Reachable,
+    /// Masked link is behind a captcha; the mirror itself may be fine
+    Captcha,
+    Dead(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkProbe {
+    pub hosting: String,
+    pub status: ProbeStatus,
+    pub size: Option<u64>,
+    pub file_name: Option<String>,
+}
+
+impl DownloadLink {
+    /// Check the link without downloading the body.
+    pub async fn probe(&self) -> LinkProbe {
+        let mut probe = LinkProbe {
+            hosting: self.hosting_name(),
+            status: ProbeStatus::Reachable,
+            size: None,
+            file_name: None,
+        };
+        match self.probe_inner(&mut probe).await {
+            Ok(()) => {}
+            Err(DownloadError::Captcha) => probe.status = ProbeStatus::Captcha,
+            Err(e) => probe.status = ProbeStatus::Dead(format!("{e:?}")),
+        }
+        probe
+    }
+
+    async fn probe_inner(&self, probe: &mut LinkProbe) -> Result<(), DownloadError> {
+        let request = match self {
+            DownloadLink::Http(url) => {
+                DirectRequest::Http(reqwest::Request::new(reqwest::Method::GET, url.clone()))
+            }
+            _ => self
+                .clone()
+                .get_direct()
+                .await?
+                .get()
+                .await
+                .map_err(DownloadError::DirectLinkFailed)?,
+        };
+
+        match request {
+            DirectRequest::Http(mut request) => {
+                // Ask for the first bytes: enough to sniff the file type and to learn the total
+                // size from Content-Range
+                request.headers_mut().insert(
+                    RANGE,
+                    reqwest::header::HeaderValue::from_static("bytes=0-7"),
+                );
+                let mut resp = crate::net::hosting_client()
+                    .execute(request)
+                    .await
+                    .map_err(DownloadError::Request)?;
+                let status = resp.status();
+                if !status.is_success() {
+                    probe.status = ProbeStatus::Dead(format!("HTTP {status}"));
+                    return Ok(());
+                }
+                // Same check as a download: pages instead of files fail with a typed error
+                validate_response(&mut resp).await?;
+                let header = |name| resp.headers().get(name).and_then(|v| v.to_str().ok());
+                probe.size = header(CONTENT_RANGE)
+                    .and_then(total_from_content_range)
+                    .or_else(|| {
+                        // Server ignored the range and would send the whole file
+                        header(CONTENT_LENGTH).and_then(|v| v.parse().ok())
+                    });
+                probe.file_name = header(CONTENT_DISPOSITION)
+                    .and_then(file_name_from_disposition)
+                    .or_else(|| {
+                        resp.url()
+                            .path_segments()
+                            .and_then(|mut s| s.next_back())
+                            .filter(|s| !s.is_empty())
+                            .map(|s| s.to_string())
+                    });
+                // Dropping the response closes the connection without reading the body
+            }
+            DirectRequest::MegaPublicUrl(url) => {
+                let mega_client = mega::ClientBuilder::new()
+                    .https(true)
+                    .build(crate::net::hosting_client())
+                    .map_err(|_| DownloadError::MegaClientBuild)?;
+                let nodes = mega_client
+                    .fetch_public_nodes(url.as_str())
+                    .await
+                    .map_err(|_| DownloadError::MegaFetchNodes)?;
+                let file_node = nodes
+                    .iter()
+                    .find(|n| n.kind().is_file())
+                    .ok_or(DownloadError::MegaNoFileNode)?;
+                probe.size = Some(file_node.size());
+                probe.file_name = Some(file_node.name().to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// "bytes 0-0/12345" -> 12345
+fn total_from_content_range(value: &str) -> Option<u64> {
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// `attachment; filename="Game-1.0.zip"` -> Game-1.0.zip
+fn file_name_from_disposition(value: &str) -> Option<String> {
+    value
+        .split(';')
+        .filter_map(|part| part.trim().split_once('='))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case("filename"))
+        .map(|(_, v)| v.trim().trim_matches('"').to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_header_parsing() {
+        assert_eq!(
+            total_from_content_range("bytes 0-0/8589934592"),
+            Some(8589934592)
+        );
+        assert_eq!(total_from_content_range("bytes */*"), None);
+        assert_eq!(
+            file_name_from_disposition(r#"attachment; filename="Game-1.0-pc.zip""#).as_deref(),
+            Some("Game-1.0-pc.zip")
+        );
+        assert_eq!(
+            file_name_from_disposition("inline; filename*=UTF-8''x.zip; filename=x.zip").as_deref(),
+            Some("x.zip")
+        );
+        assert_eq!(file_name_from_disposition("inline"), None);
+    }
+}
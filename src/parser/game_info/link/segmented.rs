@@ -0,0 +1,317 @@
+// Segmented downloads: hosts that throttle each connection (pixeldrain, catbox) are fetched as
+// several byte ranges at once. The file is preallocated and every range writes at its own
+// offset; a range whose connection drops is requested again from where it stopped. The first
+// response is kept as the first range, so no request is wasted.
+
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use reqwest::header::{ACCEPT_RANGES, HeaderValue, RANGE};
+use reqwest::{Request, Response, StatusCode};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::download::{extract_downloaded, remove_partial};
+use super::space::is_out_of_space;
+use crate::game_download::throttle::DownloadThrottle;
+use crate::game_download::{GameDownloadStatus, Progress};
+
+/// Ranges smaller than this aren't worth another connection
+const MIN_SEGMENT: u64 = 4 * 1024 * 1024;
+/// Attempts per range after its connection fails
+const SEGMENT_RETRIES: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Whether the response allows a segmented download.
+pub(super) fn accepts_ranges(resp: &Response) -> bool {
+    resp.headers()
+        .get(ACCEPT_RANGES)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("bytes"))
+}
+
+/// Split `total` bytes into at most `wanted` ranges of at least `MIN_SEGMENT`.
+pub(super) fn plan_segments(total: u64, wanted: u8) -> Vec<Range<u64>> {
+    let count = u64::from(wanted).min(total / MIN_SEGMENT).max(1);
+    let size = total.div_ceil(count);
+    (0..count)
+        .map(|i| i * size..((i + 1) * size).min(total))
+        .collect()
+}
+
+/// Where a segmented download goes and how it is split
+pub(super) struct SegmentedTarget {
+    pub file: tokio::fs::File,
+    pub filepath: PathBuf,
+    /// Bytes already read from the first response while validating it
+    pub prefix: Vec<u8>,
+    pub total: u64,
+    pub ranges: Vec<Range<u64>>,
+}
+
+enum SegmentError {
+    /// The UI dropped the download
+    Cancelled,
+    /// Worth another request from the current offset
+    Retry(String),
+    /// The hosting refused the range; another request won't help
+    Hosting(String),
+    /// Disk or request setup failed; not the hosting's fault
+    Local(String),
+}
+
+/// Shared by all ranges of one download
+struct Segments {
+    request: Request,
+    filepath: PathBuf,
+    total: u64,
+    done: AtomicU64,
+    sd: UnboundedSender<GameDownloadStatus>,
+    throttle: Arc<DownloadThrottle>,
+}
+
+impl Segments {
+    fn report(&self, bytes: u64) -> Result<(), SegmentError> {
+        let done = self.done.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.sd
+            .send(GameDownloadStatus::Transferred {
+                done,
+                total: self.total,
+            })
+            .map_err(|_| SegmentError::Cancelled)
+    }
+
+    async fn request_range(&self, range: &Range<u64>) -> Result<Response, SegmentError> {
+        let mut request = self
+            .request
+            .try_clone()
+            .ok_or_else(|| SegmentError::Local("Request can't be repeated".to_string()))?;
+        let value = format!("bytes={}-{}", range.start, range.end - 1);
+        request
+            .headers_mut()
+            .insert(RANGE, HeaderValue::from_str(&value).unwrap());
+        let resp = crate::net::hosting_client()
+            .execute(request)
+            .await
+            .map_err(|e| SegmentError::Retry(format!("Range request failed: {e}")))?;
+        match resp.status() {
+            StatusCode::PARTIAL_CONTENT => Ok(resp),
+            // A whole-file answer would overwrite the other ranges
+            StatusCode::OK => Err(SegmentError::Hosting(
+                "Server ignored the Range header".to_string(),
+            )),
+            status if status.is_server_error() || status.as_u16() == 429 => {
+                Err(SegmentError::Retry(format!("HTTP {status}")))
+            }
+            status => Err(SegmentError::Hosting(format!("HTTP {status}"))),
+        }
+    }
+
+    /// Write one range, reconnecting from the current offset when the connection fails.
+    /// `first` is a response already positioned at `range.start`.
+    async fn fetch(
+        &self,
+        mut range: Range<u64>,
+        mut first: Option<Response>,
+    ) -> Result<(), SegmentError> {
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(&self.filepath)
+            .await
+            .map_err(|e| {
+                SegmentError::Local(format!("Open {} failed: {e}", self.filepath.display()))
+            })?;
+        let mut failures = 0;
+        while range.start < range.end {
+            let result = match first.take() {
+                Some(resp) => Ok(resp),
+                None => self.request_range(&range).await,
+            };
+            let result = match result {
+                Ok(resp) => self.stream(resp, &mut range, &mut file).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => {}
+                Err(SegmentError::Retry(msg)) if failures < SEGMENT_RETRIES => {
+                    failures += 1;
+                    log::warn!(
+                        "Range {}-{} failed ({msg}), retry {failures}/{SEGMENT_RETRIES}",
+                        range.start,
+                        range.end
+                    );
+                    tokio::time::sleep(RETRY_DELAY * failures).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        file.flush()
+            .await
+            .map_err(|e| SegmentError::Local(format!("Couldn't write data to disk: {e}")))
+    }
+
+    /// Copy the body into the file until the range is complete; `range.start` follows the
+    /// written offset.
+    async fn stream(
+        &self,
+        mut resp: Response,
+        range: &mut Range<u64>,
+        file: &mut tokio::fs::File,
+    ) -> Result<(), SegmentError> {
+        file.seek(SeekFrom::Start(range.start))
+            .await
+            .map_err(|e| SegmentError::Local(format!("Seek failed: {e}")))?;
+        while range.start < range.end {
+            let bytes = match resp.chunk().await {
+                Ok(Some(bytes)) => bytes,
+                Ok(None) => return Err(SegmentError::Retry("Connection closed early".to_string())),
+                Err(e) => return Err(SegmentError::Retry(format!("Error reading chunk: {e}"))),
+            };
+            // The first response runs to the end of the file; stop at the range end
+            let take = (bytes.len() as u64).min(range.end - range.start) as usize;
+            file.write_all(&bytes[..take]).await.map_err(|e| {
+                SegmentError::Local(if is_out_of_space(&e) {
+                    format!("Disk full while downloading to {}", self.filepath.display())
+                } else {
+                    format!("Couldn't write data to disk: {e}")
+                })
+            })?;
+            range.start += take as u64;
+            self.throttle.consume(take as u64, &self.sd).await;
+            self.report(take as u64)?;
+        }
+        Ok(())
+    }
+}
+
+/// Download `target.total` bytes over `target.ranges`, starting from `resp` (which already
+/// delivered `target.prefix`), then extract like a single-stream download. Returns false if the
+/// file couldn't be preallocated.
+pub(super) async fn start_segmented_task(
+    resp: Response,
+    request: Request,
+    sd: UnboundedSender<GameDownloadStatus>,
+    target: SegmentedTarget,
+    throttle: Arc<DownloadThrottle>,
+) -> bool {
+    let SegmentedTarget {
+        mut file,
+        filepath,
+        prefix,
+        total,
+        ranges,
+    } = target;
+    if let Err(e) = preallocate(&mut file, total, &prefix).await {
+        log::error!("Preallocating {} failed: {e}", filepath.display());
+        let msg = if is_out_of_space(&e) {
+            format!("Disk full while downloading to {}", filepath.display())
+        } else {
+            "Couldn't write data to disk".to_string()
+        };
+        let _ = sd.send(GameDownloadStatus::Downloading(Progress::Error(msg)));
+        drop(file);
+        remove_partial(&filepath);
+        return false;
+    }
+    drop(file);
+    log::info!(
+        "downloading {} in {} ranges",
+        filepath.display(),
+        ranges.len()
+    );
+
+    tokio::spawn(async move {
+        let segments = Segments {
+            request,
+            filepath: filepath.clone(),
+            total,
+            done: AtomicU64::new(0),
+            sd: sd.clone(),
+            throttle,
+        };
+        if segments.report(prefix.len() as u64).is_err() {
+            remove_partial(&filepath);
+            return;
+        }
+        // The first range continues the response that delivered the prefix
+        let mut first = Some(resp);
+        let fetches = ranges.into_iter().map(|range| {
+            let resp = first.take();
+            let range = match resp {
+                Some(_) => range.start + prefix.len() as u64..range.end,
+                None => range,
+            };
+            segments.fetch(range, resp)
+        });
+        match futures::future::try_join_all(fetches).await {
+            Ok(_) => {
+                log::info!("download completed");
+                extract_downloaded(&sd, filepath).await;
+            }
+            Err(SegmentError::Cancelled) => {
+                log::info!("download cancelled, removing {}", filepath.display());
+                remove_partial(&filepath);
+            }
+            Err(SegmentError::Retry(msg)) | Err(SegmentError::Hosting(msg)) => {
+                log::error!("segmented download failed: {msg}");
+                let _ = sd.send(GameDownloadStatus::HostingFailed);
+                let _ = sd.send(GameDownloadStatus::Downloading(Progress::Error(msg)));
+                remove_partial(&filepath);
+            }
+            Err(SegmentError::Local(msg)) => {
+                log::error!("segmented download failed: {msg}");
+                let _ = sd.send(GameDownloadStatus::Downloading(Progress::Error(msg)));
+                remove_partial(&filepath);
+            }
+        }
+    });
+
+    tokio::task::yield_now().await;
+    true
+}
+
+/// Reserve the whole file up front and write the already-read prefix at its start.
+async fn preallocate(file: &mut tokio::fs::File, total: u64, prefix: &[u8]) -> std::io::Result<()> {
+    file.set_len(total).await?;
+    file.write_all(prefix).await?;
+    file.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_segments() {
+        const MIB: u64 = 1024 * 1024;
+        // Too small to split
+        assert_eq!(plan_segments(3 * MIB, 4), vec![0..3 * MIB]);
+        assert_eq!(plan_segments(100 * MIB, 1), vec![0..100 * MIB]);
+        // Fewer ranges than asked when each would be under MIN_SEGMENT
+        assert_eq!(plan_segments(10 * MIB, 8).len(), 2);
+
+        let total = 100 * MIB + 3;
+        let ranges = plan_segments(total, 4);
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges[0].start, 0);
+        assert_eq!(ranges[3].end, total);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preallocate_writes_prefix() {
+        let path = std::env::temp_dir().join(format!("f95-segmented-{}", uuid::Uuid::new_v4()));
+        let mut file = tokio::fs::File::create(&path).await.unwrap();
+        preallocate(&mut file, 16, b"PK\x03\x04").await.unwrap();
+        let data = std::fs::read(&path).unwrap();
+        assert_eq!(data.len(), 16);
+        assert_eq!(&data[..4], b"PK\x03\x04");
+        std::fs::remove_file(path).unwrap();
+    }
+}
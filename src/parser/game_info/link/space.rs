@@ -0,0 +1,221 @@
+// Disk-space preflight: compare archive and extracted sizes with the free space on the temp and
+// extract volumes, so a download fails up front instead of filling the disk halfway through.
+
+use std::fs::File as StdFile;
+use std::path::{Path, PathBuf};
+
+use zip::ZipArchive;
+
+use crate::app::settings::APP_SETTINGS;
+
+/// Headroom left free on a volume so the system and other programs keep working
+const RESERVE_BYTES: u64 = 256 * 1024 * 1024;
+/// Typical extracted/archive size ratio; below it extraction is likely (not certain) to fail
+const EXPECTED_EXPANSION: u64 = 2;
+
+/// Check the temp and extract volumes before downloading an archive of `size` bytes.
+/// Fails when the download or its extraction can't fit; only warns when extraction may not.
+pub fn check_download_space(size: u64) -> Result<(), String> {
+    let (temp_dir, extract_dir) = {
+        let s = APP_SETTINGS.read().unwrap();
+        (s.temp_dir.clone(), s.extract_dir.clone())
+    };
+
+    if let Some(free) =
+        free_space(&temp_dir).filter(|free| *free < size.saturating_add(RESERVE_BYTES))
+    {
+        return Err(not_enough_space(&temp_dir, size, free));
+    }
+
+    let Some(free) = free_space(&extract_dir) else {
+        return Ok(());
+    };
+    // The archive stays in temp until extraction has finished
+    let shared = same_volume(&temp_dir, &extract_dir);
+    let held = if shared { size } else { 0 };
+    // Extracted files are at least as large as the archive; the exact size is only known from
+    // the archive headers once it is downloaded
+    let minimum = held.saturating_add(size);
+    if free < minimum.saturating_add(RESERVE_BYTES) {
+        return Err(not_enough_space(&extract_dir, minimum, free));
+    }
+    let expected = held.saturating_add(size.saturating_mul(EXPECTED_EXPANSION));
+    if free < expected.saturating_add(RESERVE_BYTES) {
+        log::warn!(
+            "Low disk space in {}: {} free, extracting a {} archive usually needs about {}",
+            extract_dir.display(),
+            format_size(free),
+            format_size(size),
+            format_size(expected)
+        );
+    }
+    Ok(())
+}
+
+/// Check the extract volume against the extracted size read from the archive headers.
+pub fn check_extract_space(archive_path: &Path, dest_base: &Path) -> Result<(), String> {
+    let (Some(needed), Some(free)) = (extracted_size(archive_path), free_space(dest_base)) else {
+        return Ok(());
+    };
+    if free < needed.saturating_add(RESERVE_BYTES) {
+        return Err(format!(
+            "{} The archive was kept at {}.",
+            not_enough_space(dest_base, needed, free),
+            archive_path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Total size of the files in the archive, from its headers (nothing is decompressed).
+pub fn extracted_size(archive_path: &Path) -> Option<u64> {
+    let name_lower = archive_path.file_name()?.to_str()?.to_ascii_lowercase();
+    if name_lower.ends_with(".zip") {
+        let archive = ZipArchive::new(StdFile::open(archive_path).ok()?).ok()?;
+        archive
+            .decompressed_size()
+            .and_then(|s| u64::try_from(s).ok())
+    } else if name_lower.ends_with(".7z") {
+        let archive = sevenz_rust::Archive::open(archive_path).ok()?;
+        Some(
+            archive
+                .files
+                .iter()
+                .filter(|f| !f.is_directory())
+                .map(|f| f.size())
+                .sum(),
+        )
+    } else if name_lower.ends_with(".rar") {
+        let listing = unrar::Archive::new(archive_path).open_for_listing().ok()?;
+        let mut total = 0u64;
+        for header in listing {
+            let header = header.ok()?;
+            if header.is_file() {
+                total = total.saturating_add(header.unpacked_size);
+            }
+        }
+        Some(total)
+    } else if name_lower.ends_with(".exe") {
+        std::fs::metadata(archive_path).ok().map(|m| m.len())
+    } else {
+        None
+    }
+}
+
+/// Whether an I/O error means the disk (or quota) is full.
+pub fn is_out_of_space(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::StorageFull | std::io::ErrorKind::QuotaExceeded
+    )
+}
+
+/// Bytes available to the current user on the volume holding `path`.
+/// The directory doesn't have to exist yet; its closest existing parent is used.
+pub fn free_space(path: &Path) -> Option<u64> {
+    available_bytes(&existing_ancestor(path)?)
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+fn not_enough_space(dir: &Path, needed: u64, free: u64) -> String {
+    format!(
+        "Not enough disk space in {}: needs {}, only {} free.",
+        dir.display(),
+        format_size(needed.saturating_add(RESERVE_BYTES)),
+        format_size(free)
+    )
+}
+
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().ok()?.join(path)
+    };
+    absolute
+        .ancestors()
+        .find(|p| p.exists())
+        .map(Path::to_path_buf)
+}
+
+#[cfg(unix)]
+fn same_volume(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    let dev = |p: &Path| {
+        existing_ancestor(p)
+            .and_then(|p| std::fs::metadata(p).ok())
+            .map(|m| m.dev())
+    };
+    matches!((dev(a), dev(b)), (Some(x), Some(y)) if x == y)
+}
+
+#[cfg(windows)]
+fn same_volume(a: &Path, b: &Path) -> bool {
+    let root = |p: &Path| {
+        existing_ancestor(p)
+            .and_then(|p| std::fs::canonicalize(p).ok())
+            .and_then(|p| {
+                p.components()
+                    .next()
+                    .map(|c| c.as_os_str().to_ascii_lowercase())
+            })
+    };
+    matches!((root(a), root(b)), (Some(x), Some(y)) if x == y)
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn available_bytes(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some((stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64))
+}
+
+#[cfg(windows)]
+fn available_bytes(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut free: u64 = 0;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut free,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(free)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_space_of_missing_dir_uses_parent() {
+        let missing = std::env::temp_dir().join(format!("f95-space-{}", uuid::Uuid::new_v4()));
+        assert!(free_space(&missing.join("nested")).is_some());
+        assert!(same_volume(&missing, &std::env::temp_dir()));
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024 / 2), "1.5 GB");
+    }
+}
@@ -0,0 +1,198 @@
+// Response validation: hostings often answer 200 with an HTML "file not found" / "quota
+// exceeded" page. Sniff the first bytes before anything is written so such pages fail as typed
+// errors (and the next mirror is tried) instead of being saved and extracted as an archive.
+
+use reqwest::Response;
+use reqwest::header::CONTENT_TYPE;
+
+use super::DownloadError;
+
+/// How much of a suspected error page is read to classify it
+const MAX_PAGE_BYTES: usize = 64 * 1024;
+/// Enough bytes for every signature below
+const SNIFF_BYTES: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileKind {
+    Zip,
+    Rar,
+    SevenZ,
+    Exe,
+    Gzip,
+}
+
+impl FileKind {
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        const SIGNATURES: [(&[u8], FileKind); 6] = [
+            (b"PK\x03\x04", FileKind::Zip),
+            // Empty zip
+            (b"PK\x05\x06", FileKind::Zip),
+            (b"Rar!\x1a\x07", FileKind::Rar),
+            (b"7z\xbc\xaf\x27\x1c", FileKind::SevenZ),
+            (b"MZ", FileKind::Exe),
+            (b"\x1f\x8b", FileKind::Gzip),
+        ];
+        SIGNATURES
+            .iter()
+            .find(|(magic, _)| bytes.starts_with(magic))
+            .map(|(_, kind)| *kind)
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            FileKind::Zip => "zip",
+            FileKind::Rar => "rar",
+            FileKind::SevenZ => "7z",
+            FileKind::Exe => "exe",
+            FileKind::Gzip => "gz",
+        }
+    }
+
+    /// Whether extraction can handle the file (an exe is installed as is). There is no tar
+    /// support, so a .gz/.tar.gz would only fail after the whole download.
+    pub fn supported(self) -> bool {
+        !matches!(self, FileKind::Gzip)
+    }
+}
+
+/// Check the response before it is saved. Returns the bytes already read from the body (to be
+/// written first) and the sniffed file type, if recognised.
+pub async fn validate_response(
+    resp: &mut Response,
+) -> Result<(Vec<u8>, Option<FileKind>), DownloadError> {
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(match status.as_u16() {
+            404 | 410 => DownloadError::FileRemoved,
+            429 | 509 => DownloadError::QuotaExceeded,
+            _ => DownloadError::NotAnArchive(format!("HTTP {status}")),
+        });
+    }
+    let content_type = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let mut prefix = Vec::new();
+    while prefix.len() < SNIFF_BYTES {
+        match resp.chunk().await.map_err(DownloadError::Request)? {
+            Some(chunk) => prefix.extend_from_slice(&chunk),
+            None => break,
+        }
+    }
+    if let Some(kind) = FileKind::sniff(&prefix) {
+        if !kind.supported() {
+            log::warn!(
+                "{} is a .{} file, which can't be extracted",
+                resp.url(),
+                kind.extension()
+            );
+            return Err(DownloadError::UnsupportedArchive(kind.extension()));
+        }
+        return Ok((prefix, Some(kind)));
+    }
+
+    let textual = content_type.starts_with("text/")
+        || content_type.contains("json")
+        || content_type.contains("xml")
+        || looks_like_markup(&prefix);
+    if !textual {
+        // Unknown binary: let extraction decide
+        return Ok((prefix, None));
+    }
+
+    while prefix.len() < MAX_PAGE_BYTES {
+        match resp.chunk().await.map_err(DownloadError::Request)? {
+            Some(chunk) => prefix.extend_from_slice(&chunk),
+            None => break,
+        }
+    }
+    let page = String::from_utf8_lossy(&prefix);
+    let err = classify_error_page(&page, &content_type);
+    log::warn!("{} returned a page instead of a file: {err:?}", resp.url());
+    Err(err)
+}
+
+fn looks_like_markup(prefix: &[u8]) -> bool {
+    let trimmed = prefix.trim_ascii_start();
+    trimmed.starts_with(b"<") || trimmed.starts_with(b"{")
+}
+
+/// Map a hosting's error page to a typed error by its wording.
+fn classify_error_page(page: &str, content_type: &str) -> DownloadError {
+    const QUOTA: [&str; 7] = [
+        "quota",
+        "bandwidth limit",
+        "download limit",
+        "limit exceeded",
+        "limit reached",
+        "too many downloads",
+        "rate limit",
+    ];
+    const REMOVED: [&str; 9] = [
+        "file not found",
+        "file was removed",
+        "has been removed",
+        "file was deleted",
+        "has been deleted",
+        "no longer available",
+        "does not exist",
+        "has expired",
+        "404 not found",
+    ];
+    let lower = page.to_lowercase();
+    if QUOTA.iter().any(|p| lower.contains(p)) {
+        DownloadError::QuotaExceeded
+    } else if REMOVED.iter().any(|p| lower.contains(p)) {
+        DownloadError::FileRemoved
+    } else {
+        let kind = if content_type.is_empty() {
+            "text"
+        } else {
+            content_type.split(';').next().unwrap_or(content_type)
+        };
+        DownloadError::NotAnArchive(kind.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_and_classify_error_pages() {
+        assert_eq!(FileKind::sniff(b"PK\x03\x04rest"), Some(FileKind::Zip));
+        assert_eq!(
+            FileKind::sniff(b"Rar!\x1a\x07\x01\x00"),
+            Some(FileKind::Rar)
+        );
+        assert_eq!(
+            FileKind::sniff(b"7z\xbc\xaf\x27\x1c\x00\x04"),
+            Some(FileKind::SevenZ)
+        );
+        assert_eq!(FileKind::sniff(b"<!DOCTYPE html>"), None);
+        let gzip = FileKind::sniff(b"\x1f\x8b\x08\x00").unwrap();
+        assert_eq!(gzip, FileKind::Gzip);
+        assert!(!gzip.supported());
+        assert!(FileKind::Zip.supported());
+        assert!(looks_like_markup(b"\r\n  <html>"));
+
+        assert!(matches!(
+            classify_error_page("<html><h1>Download quota exceeded</h1></html>", "text/html"),
+            DownloadError::QuotaExceeded
+        ));
+        assert!(matches!(
+            classify_error_page(
+                "<p>The file you requested has been deleted.</p>",
+                "text/html"
+            ),
+            DownloadError::FileRemoved
+        ));
+        assert!(matches!(
+            classify_error_page("<html>Welcome</html>", "text/html; charset=utf-8"),
+            DownloadError::NotAnArchive(kind) if kind == "text/html"
+        ));
+    }
+}
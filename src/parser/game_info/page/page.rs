@@ -7,7 +7,6 @@ use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::parser::game_info::DownloadLink;
-use crate::parser::game_info::cookies;
 use crate::parser::game_info::{Platform, PlatformDownloads};
 
 lazy_static! {
@@ -59,7 +58,6 @@ impl F95PageUrl {
         let client = crate::net::client();
         let text = client
             .get(self.0.clone())
-            .header("Cookie", cookies())
             .send()
             .await?
             .text()
@@ -1,11 +1,8 @@
 use lazy_static::lazy_static;
 use regex::Regex;
-use reqwest::{Client, StatusCode, Url};
+use reqwest::{StatusCode, Url};
 
-use super::{
-    cookies,
-    page::{F95Page, F95PageUrl},
-};
+use super::page::{F95Page, F95PageUrl};
 use crate::tags::TAGS;
 use std::{fmt, time::Duration};
 
@@ -21,7 +18,6 @@ pub struct ThreadMeta {
 
 #[derive(Debug)]
 pub enum FetchThreadMetaError {
-    BuildClient,
     Request(reqwest::Error),
     ReadText(reqwest::Error),
     OgTitleMissing,
@@ -34,7 +30,6 @@ pub enum FetchThreadMetaError {
 impl fmt::Display for FetchThreadMetaError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            FetchThreadMetaError::BuildClient => write!(f, "failed to build HTTP client"),
             FetchThreadMetaError::Request(e) => write!(f, "request error: {}", e),
             FetchThreadMetaError::ReadText(e) => write!(f, "read body error: {}", e),
             FetchThreadMetaError::OgTitleMissing => write!(f, "OG title not found or malformed"),
@@ -52,30 +47,33 @@ impl std::error::Error for FetchThreadMetaError {}
 
 lazy_static! {
     static ref RE_OG_TITLE: Regex = Regex::new(r#"</span>.* *\[.*\] *\[.*\]<"#).unwrap();
-    static ref RE_ATTACH: Regex = Regex::new(
-        r#"href="(https://attachments\.f95zone\.to/\d+/\d+/\d+_[A-Za-z0-9_\-]+\.[A-Za-z0-9]+(?:\?[^\s"'<>]*)?)""#
-    ).unwrap();
-    static ref RE_COVER: Regex = Regex::new(
-        r#"src="(https://attachments\.f95zone\.to/\d+/\d+/\d+_[A-Za-z0-9_\-]+\.[A-Za-z0-9]+(?:\?[^\s"'<>]*)?)""#
-    ).unwrap();
     static ref RE_TAG_BLOCK: Regex = Regex::new(r#"(?s)<span class="js-tagList">(.+?)</span>"#).unwrap();
     static ref RE_TAG_TEXT: Regex = Regex::new(r#">([^<>]+)<"#).unwrap();
 }
 
+/// Links (`href`) and images (`src`) pointing at files on the attachments CDN.
+fn attachment_regexes(attachments: &str) -> (Regex, Regex) {
+    let file = format!(
+        r#"({}\d+/\d+/\d+_[A-Za-z0-9_\-]+\.[A-Za-z0-9]+(?:\?[^\s"'<>]*)?)""#,
+        regex::escape(attachments)
+    );
+    (
+        Regex::new(&format!(r#"href="{file}"#)).unwrap(),
+        Regex::new(&format!(r#"src="{file}"#)).unwrap(),
+    )
+}
+
 /// Fetch thread page and extract cover, screenshots and tag IDs.
 /// Returns typed errors for better diagnostics. If a cover is not found,
 /// falls back to the first screenshot if available.
 pub async fn fetch_thread_meta(thread_id: u64) -> Result<ThreadMeta, FetchThreadMetaError> {
-    let url = format!("https://f95zone.to/threads/{}/", thread_id);
+    let endpoints = crate::net::endpoints::current();
+    let url = endpoints.thread_url(thread_id);
 
-    let client = Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/125.0.0.0 Safari/537.36")
-        .build()
-        .map_err(|_| FetchThreadMetaError::BuildClient)?;
+    let client = crate::net::client();
 
     let resp: reqwest::Response = client
         .get(&url)
-        .header("Cookie", cookies())
         .send()
         .await
         .map_err(FetchThreadMetaError::Request)?;
@@ -137,7 +135,8 @@ pub async fn fetch_thread_meta(thread_id: u64) -> Result<ThreadMeta, FetchThread
     let image_extensions = ["png", "jpg", "jpeg", "gif", "webp", "bmp"];
     let mut screens: Vec<String> = Vec::new();
     let mut seen = std::collections::HashSet::new();
-    for cap in RE_ATTACH.captures_iter(&text) {
+    let (re_attach, re_cover) = attachment_regexes(&endpoints.attachments);
+    for cap in re_attach.captures_iter(&text) {
         let s = cap.get(1).unwrap().as_str().to_string();
         // Extract extension (handle query strings like "image.png?hash=123")
         let ext = s
@@ -154,7 +153,7 @@ pub async fn fetch_thread_meta(thread_id: u64) -> Result<ThreadMeta, FetchThread
     }
 
     // Cover: prefer explicit cover; fallback to first screenshot if available.
-    let cover = match RE_COVER
+    let cover = match re_cover
         .captures(&text)
         .and_then(|cap| cap.get(1))
         .map(|m| m.as_str().to_string())
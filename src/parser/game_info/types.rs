@@ -14,7 +14,7 @@ impl ThreadId {
         self.0
     }
     pub fn get_page(&self) -> F95PageUrl {
-        let url = format!("https://f95zone.to/threads/{}/", self.0);
+        let url = crate::net::endpoints::current().thread_url(self.0);
         F95PageUrl(Url::from_str(&url).unwrap())
     }
 }
@@ -68,7 +68,7 @@ impl From<&str> for Platform {
     }
 }
 
-#[derive(Getters, Debug)]
+#[derive(Getters, Debug, Clone)]
 pub struct PlatformDownloads {
     platform: Platform,
     links: Vec<DownloadLink>,
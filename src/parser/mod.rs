@@ -17,11 +17,12 @@ use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 
 use crate::{
-    parser::game_info::{ThreadId, cookies},
+    parser::game_info::ThreadId,
     types::{DateLimit, Sorting},
 };
 
-pub const BASE_URL: &str = "https://f95zone.to/sam/latest_alpha/latest_data.php";
+/// Latest-updates list endpoint, relative to the site base (see `net::endpoints`)
+pub const LIST_PATH: &str = "sam/latest_alpha/latest_data.php";
 
 pub mod game_info;
 
@@ -113,7 +114,7 @@ pub fn normalize_url(s: &str) -> String {
     if s.starts_with("http://") || s.starts_with("https://") {
         s.to_string()
     } else {
-        format!("https://f95zone.to{}", s)
+        crate::net::endpoints::current().url(s)
     }
 }
 
@@ -165,12 +166,9 @@ pub async fn fetch_image_f95_with_ref(
     };
 
     // If server forces AVIF for attachments, try preview CDN fallback which serves WebP/JPEG
-    if content_type.contains("avif") && url.starts_with("https://attachments.f95zone.to/") {
-        let alt = url.replacen(
-            "https://attachments.f95zone.to/",
-            "https://preview.f95zone.to/",
-            1,
-        );
+    let endpoints = crate::net::endpoints::current();
+    if content_type.contains("avif") && url.starts_with(&endpoints.attachments) {
+        let alt = url.replacen(&endpoints.attachments, &endpoints.preview, 1);
         log::info!(
             "fetch_image: AVIF from attachments, trying preview fallback: {}",
             alt
@@ -248,7 +246,7 @@ pub async fn fetch_image_f95_with_ref(
 
 /// Backwards-compatible helper that uses site root as referer.
 pub async fn fetch_image_f95(url: &str) -> Result<(usize, usize, Vec<u8>), String> {
-    fetch_image_f95_with_ref(url, "https://f95zone.to/").await
+    fetch_image_f95_with_ref(url, &crate::net::endpoints::current().base).await
 }
 
 #[derive(Debug)]
@@ -442,11 +440,12 @@ pub async fn fetch_list_page(page: u32, filters: &F95Filters) -> Result<F95Msg,
     // cache buster
     params.push(("_".into(), cache_buster.to_string()));
 
+    let list_url = crate::net::endpoints::current().url(LIST_PATH);
+
     // Perform request, and if server responds with 429 (Too Many Requests),
     // wait 1 second before retrying once to avoid immediate hammering.
     let mut raw_resp = client
-        .get(BASE_URL)
-        .header("Cookie", cookies())
+        .get(&list_url)
         .query(&params)
         .send()
         .await?;
@@ -456,8 +455,7 @@ pub async fn fetch_list_page(page: u32, filters: &F95Filters) -> Result<F95Msg,
         log::warn!("fetch_list_page: received 429 Too Many Requests; delaying 1s before retry");
         tokio::time::sleep(std::time::Duration::from_secs(1)).await;
         raw_resp = client
-            .get(BASE_URL)
-            .header("Cookie", cookies())
+            .get(&list_url)
             .query(&params)
             .send()
             .await?;
@@ -35,6 +35,27 @@ impl Sorting {
     }
 }
 
+// Client-side ordering of the Library view (catalog sorting is done by the API)
+#[derive(strum::EnumCount, strum::EnumIter, PartialEq, Clone, strum::Display, Default, Debug)]
+pub enum LibrarySorting {
+    #[default]
+    Added,
+    Title,
+    Playtime,
+    LastPlayed,
+}
+impl EnumWithAlternativeNames for LibrarySorting {
+    fn alternative_name(&self) -> &'static str {
+        use LibrarySorting::*;
+        match self {
+            Added => "📥",
+            Title => "🔤",
+            Playtime => "⏱",
+            LastPlayed => "🎮",
+        }
+    }
+}
+
 #[derive(strum::EnumCount, strum::EnumIter, PartialEq, Clone, Copy, Default)]
 pub enum DateLimit {
     #[default]
@@ -127,6 +148,17 @@ impl crate::views::filters::LocalizableName for Sorting {
     }
 }
 
+impl crate::views::filters::LocalizableName for LibrarySorting {
+    fn loc_key(&self) -> &'static str {
+        match self {
+            LibrarySorting::Added => "library-sorting-added",
+            LibrarySorting::Title => "library-sorting-title",
+            LibrarySorting::Playtime => "library-sorting-playtime",
+            LibrarySorting::LastPlayed => "library-sorting-last-played",
+        }
+    }
+}
+
 impl crate::views::filters::LocalizableName for DateLimit {
     fn loc_key(&self) -> &'static str {
         match self {
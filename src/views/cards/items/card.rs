@@ -1,15 +1,23 @@
 use eframe::egui::{self, Color32, RichText, Rounding, Stroke};
 
 use crate::app::settings::{
-    delete_downloaded_game, downloaded_game_folder, hide_thread, is_pending_download,
-    is_thread_hidden, open_in_browser, remove_pending_download, reveal_in_file_manager,
+    delete_downloaded_game, downloaded_game_folder, hide_thread, is_game_running,
+    is_pending_download, is_thread_hidden, open_in_browser, remove_pending_download,
+    reveal_in_file_manager, stop_game,
+};
+use crate::app::settings::store::{
+    set_active_launch_profile, set_game_sandbox, set_keep_local_archive,
 };
 use crate::parser::F95Thread;
 // use crate::views::cards::items::cover_hover::CoverHover;
 use super::bookmark_selector::draw_bookmark_selector_popup;
 use super::cover_hover::draw_cover;
+use super::download_url::draw_download_url_popup;
+use super::launch_profiles::draw_launch_profiles_popup;
+use super::link_check::draw_link_check_popup;
 use super::meta_row::draw_meta_row;
 use super::tags_panel::draw_tags_panel;
+use super::verify_files::draw_verify_files_popup;
 
 /// Hover info returned by thread_card so the caller can lazy-load screenshots.
 pub struct CardHover {
@@ -19,6 +27,27 @@ pub struct CardHover {
     pub selected_link: Option<crate::parser::game_info::link::DownloadLink>,
     pub refresh_clicked: bool,
     pub update_clicked: bool,
+    /// Archive picked via "Install from file…"
+    pub install_file: Option<std::path::PathBuf>,
+    /// Links of the platform build picked from the platform prompt
+    pub selected_platform: Option<Vec<crate::parser::game_info::link::DownloadLink>>,
+    /// "Download (choose platform)…" from the context menu
+    pub choose_platform_clicked: bool,
+    /// "Repair" from the verify-files popup
+    pub repair_clicked: bool,
+    /// "Install from archive library" from the context menu
+    pub library_install_clicked: bool,
+}
+
+/// Prompts of a running download, shown as badges over the cover.
+#[derive(Default, Clone, Copy)]
+pub struct DownloadPrompts<'a> {
+    /// Mirrors to pick from when no platform could be parsed
+    pub link_choices: Option<&'a [crate::parser::game_info::link::DownloadLink]>,
+    /// Masked link waiting for its captcha
+    pub captcha: Option<&'a crate::game_download::CaptchaPrompt>,
+    /// Platform builds to pick from
+    pub platform_choices: Option<&'a [crate::parser::game_info::PlatformDownloads]>,
 }
 
 /// Fixed-width card resembling F95 tiles.
@@ -32,7 +61,7 @@ pub fn thread_card(
     cover_tex: Option<&egui::TextureHandle>,
     screens: Option<&[Option<egui::TextureHandle>]>,
     progress: Option<crate::game_download::Progress>,
-    link_choices: Option<&[crate::parser::game_info::link::DownloadLink]>,
+    prompts: DownloadPrompts<'_>,
 ) -> CardHover {
     let rounding = Rounding::same(crate::ui_constants::card::ROUNDING);
     let fill = Color32::from_rgb(36, 36, 36);
@@ -44,6 +73,10 @@ pub fn thread_card(
     let mut hovered_line: Option<usize> = None;
     let mut hovered_any = false;
     let mut refresh_clicked = false;
+    let mut install_file: Option<std::path::PathBuf> = None;
+    let mut choose_platform_clicked = false;
+    let mut library_install_clicked = false;
+    let mut selected_platform_local = None;
 
     // If tags panel was open on previous frame, make bottom corners square to merge seamlessly.
     let open_id = egui::Id::new(("card_tags_open", t.thread_id));
@@ -87,13 +120,14 @@ pub fn thread_card(
                 cover_tex,
                 screens,
                 progress.clone(),
-                link_choices,
+                prompts,
             );
             hovered_any |= cover_hover.hovered;
             hovered_line = cover_hover.hovered_line;
             download_clicked |= cover_hover.download_clicked;
             // capture selected link to return outside the closure
             selected_link_local = cover_hover.selected_link;
+            selected_platform_local = cover_hover.selected_platform;
             update_clicked_local |= cover_hover.update_clicked;
 
             // Title (after cover and markers)
@@ -183,6 +217,144 @@ pub fn thread_card(
             }
         }
 
+        // Launch profile selection and editing (only for downloaded games)
+        if is_downloaded {
+            ui.menu_button(crate::localization::translate("card-context-launch-profile"), |ui| {
+                let (profiles, active) = crate::app::settings::with_settings(|st| {
+                    st.downloaded_games
+                        .iter()
+                        .find(|g| g.thread_id == thread_id)
+                        .map(|g| (g.launch_profiles.clone(), g.active_profile.clone()))
+                        .unwrap_or_default()
+                });
+                if ui
+                    .radio(
+                        active.is_none(),
+                        crate::localization::translate("launch-profiles-default"),
+                    )
+                    .clicked()
+                {
+                    set_active_launch_profile(thread_id, None);
+                    ui.close_menu();
+                }
+                for p in &profiles {
+                    if ui
+                        .radio(active.as_deref() == Some(p.name.as_str()), &p.name)
+                        .clicked()
+                    {
+                        set_active_launch_profile(thread_id, Some(p.name.clone()));
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                if ui
+                    .button(crate::localization::translate("launch-profiles-edit"))
+                    .clicked()
+                {
+                    ui.ctx().memory_mut(|m| {
+                        m.data
+                            .insert_temp(egui::Id::new(("launch_profiles_open", thread_id)), true);
+                    });
+                    ui.close_menu();
+                }
+            });
+        }
+
+        // Linux sandbox toggle (bubblewrap/firejail)
+        if cfg!(target_os = "linux") && is_downloaded {
+            let (mut sandboxed, mut allow_network) = crate::app::settings::with_settings(|st| {
+                st.downloaded_games
+                    .iter()
+                    .find(|g| g.thread_id == thread_id)
+                    .map(|g| (g.sandboxed, g.sandbox_network))
+                    .unwrap_or_default()
+            });
+            let mut changed = ui
+                .checkbox(
+                    &mut sandboxed,
+                    crate::localization::translate("card-context-sandbox"),
+                )
+                .changed();
+            if sandboxed {
+                changed |= ui
+                    .checkbox(
+                        &mut allow_network,
+                        crate::localization::translate("card-context-sandbox-network"),
+                    )
+                    .changed();
+            }
+            if changed {
+                set_game_sandbox(thread_id, sandboxed, allow_network);
+            }
+        }
+
+        // Download from a pasted URL / install from an archive downloaded outside the app
+        if !is_downloading {
+            if ui
+                .button(crate::localization::translate("card-context-check-links"))
+                .clicked()
+            {
+                ui.ctx().memory_mut(|m| {
+                    m.data
+                        .insert_temp(egui::Id::new(("link_check_open", thread_id)), true);
+                });
+                crate::game_download::link_check::start_link_check(
+                    thread_id,
+                    t.thread_id.get_page(),
+                );
+                ui.close_menu();
+            }
+            if ui
+                .button(crate::localization::translate("card-context-choose-platform"))
+                .clicked()
+            {
+                choose_platform_clicked = true;
+                ui.close_menu();
+            }
+            if ui
+                .button(crate::localization::translate("card-context-download-url"))
+                .clicked()
+            {
+                ui.ctx().memory_mut(|m| {
+                    m.data
+                        .insert_temp(egui::Id::new(("download_url_open", thread_id)), true);
+                });
+                ui.close_menu();
+            }
+            if ui
+                .button(crate::localization::translate("card-context-install-from-file"))
+                .clicked()
+            {
+                install_file = rfd::FileDialog::new()
+                    .add_filter("Archives", &["zip", "7z", "rar", "exe"])
+                    .pick_file();
+                ui.close_menu();
+            }
+            if !is_downloaded
+                && let Some((stored, _)) =
+                    crate::game_download::archive_store::find(thread_id, None)
+                && ui
+                    .button(crate::localization::translate_with(
+                        "card-context-install-from-library",
+                        &[("version", stored.version)],
+                    ))
+                    .clicked()
+            {
+                library_install_clicked = true;
+                ui.close_menu();
+            }
+            let mut keep = crate::app::settings::with_settings(|st| st.keep_local_archive);
+            if ui
+                .checkbox(
+                    &mut keep,
+                    crate::localization::translate("card-context-keep-archive"),
+                )
+                .changed()
+            {
+                set_keep_local_archive(keep);
+            }
+        }
+
         // Refresh metadata from network (for library games)
         if is_downloaded {
             if ui.button("🔄 Refresh").clicked() {
@@ -191,6 +363,23 @@ pub fn thread_card(
             }
         }
 
+        // Check installed files against the install manifest
+        if is_downloaded
+            && !is_downloading
+            && ui
+                .button(crate::localization::translate("card-context-verify-files"))
+                .clicked()
+        {
+            if let Some(folder) = downloaded_game_folder(thread_id) {
+                ui.ctx().memory_mut(|m| {
+                    m.data
+                        .insert_temp(egui::Id::new(("verify_files_open", thread_id)), true);
+                });
+                crate::game_download::integrity::start_verify(thread_id, folder);
+            }
+            ui.close_menu();
+        }
+
         // Remove pending entry (not downloading, not downloaded)
         if is_pending && !is_downloading && !is_downloaded {
             if ui.button("Remove from Library").clicked() {
@@ -200,6 +389,17 @@ pub fn thread_card(
             }
         }
 
+        // Stop a game launched from the app
+        if is_downloaded
+            && is_game_running(thread_id)
+            && ui
+                .button(crate::localization::translate("card-stop-game"))
+                .clicked()
+        {
+            stop_game(thread_id);
+            ui.close_menu();
+        }
+
         // If downloaded: allow deleting and opening folder
         if is_downloaded {
             if ui.button("Delete").clicked() {
@@ -224,6 +424,12 @@ pub fn thread_card(
     hovered_any |= area_hovered;
 
     draw_bookmark_selector_popup(ui, t.thread_id.get(), card_rect);
+    draw_launch_profiles_popup(ui, t.thread_id.get(), card_rect);
+    if let Some(link) = draw_download_url_popup(ui, t.thread_id.get(), card_rect) {
+        selected_link_local = Some(link);
+    }
+    draw_link_check_popup(ui, t.thread_id.get(), card_rect);
+    let repair_clicked = draw_verify_files_popup(ui, t.thread_id.get(), card_rect);
 
     CardHover {
         hovered: hovered_any,
@@ -232,5 +438,10 @@ pub fn thread_card(
         selected_link: selected_link_local,
         refresh_clicked,
         update_clicked: update_clicked_local,
+        install_file,
+        selected_platform: selected_platform_local,
+        choose_platform_clicked,
+        repair_clicked,
+        library_install_clicked,
     }
 }
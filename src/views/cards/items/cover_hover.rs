@@ -2,8 +2,12 @@ use eframe::egui::epaint::{Mesh, Vertex};
 use eframe::egui::{self, Color32, Label, RichText, Sense, Stroke, Vec2};
 
 use crate::app::settings as app_settings;
+use crate::parser::game_info::PlatformDownloads;
 use crate::parser::game_info::link::DownloadLink;
-use crate::{parser::F95Thread, views::cards::items::card::CardHover};
+use crate::{
+    parser::F95Thread,
+    views::cards::items::card::{CardHover, DownloadPrompts},
+};
 
 fn draw_badge_with_overlay(
     ui: &mut egui::Ui,
@@ -214,8 +218,13 @@ pub fn draw_cover(
     cover: Option<&egui::TextureHandle>,
     screens: Option<&[Option<egui::TextureHandle>]>,
     progress: Option<crate::game_download::Progress>,
-    link_choices: Option<&[DownloadLink]>,
+    prompts: DownloadPrompts<'_>,
 ) -> CardHover {
+    let DownloadPrompts {
+        link_choices,
+        captcha,
+        platform_choices,
+    } = prompts;
     let cover_h = inner_w * 9.0 / 16.0;
     let (cover_rect, _cover_resp) =
         ui.allocate_exact_size(Vec2::new(inner_w, cover_h), Sense::hover());
@@ -557,6 +566,8 @@ pub fn draw_cover(
     let is_downloaded = app_settings::downloaded_game_folder(thread.thread_id.get())
         .map(|p| p.is_dir())
         .unwrap_or(false);
+    let is_running = is_downloaded && app_settings::is_game_running(thread.thread_id.get());
+    let is_html = engine_name.as_deref() == Some("HTML");
     let icon = if is_downloaded { "▶" } else { "⬇" };
 
     if over_cover || resp.hovered() {
@@ -581,14 +592,25 @@ pub fn draw_cover(
             eframe::egui::Align2::CENTER_CENTER,
             icon,
             eframe::egui::FontId::proportional(16.0),
-            Color32::from_gray(230),
+            if is_running {
+                Color32::from_gray(120)
+            } else {
+                Color32::from_gray(230)
+            },
         );
     }
+    let resp = if is_running {
+        resp.on_hover_text(crate::localization::translate("card-already-running"))
+    } else {
+        resp
+    };
 
     if resp.clicked() {
         log::info!("cover button clicked for thread {}", thread.thread_id.get());
-        if is_downloaded {
-            app_settings::run_downloaded_game(thread.thread_id.get());
+        if is_running {
+            log::info!("thread {} is already running", thread.thread_id.get());
+        } else if is_downloaded {
+            app_settings::run_downloaded_game(thread.thread_id.get(), &thread.title, is_html);
         } else {
             download_clicked = true;
         }
@@ -605,8 +627,10 @@ pub fn draw_cover(
                 "manual cover button click for thread {}",
                 thread.thread_id.get()
             );
-            if is_downloaded {
-                app_settings::run_downloaded_game(thread.thread_id.get());
+            if is_running {
+                log::info!("thread {} is already running", thread.thread_id.get());
+            } else if is_downloaded {
+                app_settings::run_downloaded_game(thread.thread_id.get(), &thread.title, is_html);
             } else {
                 download_clicked = true;
             }
@@ -614,7 +638,32 @@ pub fn draw_cover(
     }
 
     let mut update_clicked = false;
-    if crate::app::game_updates::ui::is_update_available(thread.thread_id.get()) {
+    if is_running {
+        // Running badge takes the update badge slot while the game is open
+        draw_badge_with_overlay(
+            ui,
+            thread.thread_id.get(),
+            cover_rect,
+            "running",
+            &crate::localization::translate("card-running"),
+            Color32::from_rgb(40, 140, 90),
+            |ui| {
+                if let Some(game) = app_settings::running_game(thread.thread_id.get()) {
+                    let mins = game.started.elapsed().as_secs() / 60;
+                    ui.label(crate::localization::translate_with(
+                        "card-running-for",
+                        &[("minutes", mins.to_string())],
+                    ));
+                }
+                if ui
+                    .button(crate::localization::translate("card-stop-game"))
+                    .clicked()
+                {
+                    app_settings::stop_game(thread.thread_id.get());
+                }
+            },
+        );
+    } else if crate::app::game_updates::ui::is_update_available(thread.thread_id.get()) {
         let update_badge_clicked =
             draw_clickable_update_badge(ui, thread.thread_id.get(), cover_rect);
         if update_badge_clicked {
@@ -624,6 +673,7 @@ pub fn draw_cover(
 
     // Resolve progress error (if any) to show error badge
     let mut selected_link: Option<DownloadLink> = None;
+    let mut selected_platform: Option<Vec<DownloadLink>> = None;
     let download_error: Option<&str> = match &progress {
         Some(crate::game_download::Progress::Error(s)) => Some(s.as_str()),
         _ => None,
@@ -651,7 +701,8 @@ pub fn draw_cover(
                                 .collect::<String>();
                             format!("{}/{}", d.hosting.to_string(), last)
                         }
-                        crate::parser::game_info::link::DownloadLink::Masked(u) => {
+                        crate::parser::game_info::link::DownloadLink::Masked(u)
+                        | crate::parser::game_info::link::DownloadLink::Http(u) => {
                             format!("{}{}", u.domain().unwrap_or_default(), u.path())
                         }
                     };
@@ -675,6 +726,95 @@ pub fn draw_cover(
         );
     }
 
+    // Select Platform badge: no preferred platform matched, or the user asked to choose
+    if let Some(choices) = platform_choices {
+        draw_badge_with_overlay(
+            ui,
+            thread.thread_id.get(),
+            cover_rect,
+            "dl_platform",
+            &crate::localization::translate("card-select-platform"),
+            Color32::from_rgb(60, 120, 200),
+            |ui| {
+                ui.set_max_width(250.);
+                for pd in choices.iter().filter(|pd| !pd.links().is_empty()) {
+                    if ui
+                        .add(
+                            Label::new(RichText::new(platform_choice_label(pd)))
+                                .wrap(true)
+                                .sense(Sense::click()),
+                        )
+                        .on_hover_cursor(egui::CursorIcon::PointingHand)
+                        .clicked()
+                    {
+                        selected_platform = Some(pd.links().clone());
+                    }
+                }
+            },
+        );
+    }
+
+    // Captcha badge: a masked link waits for the user to pass its captcha in the browser
+    if let Some(prompt) = captcha {
+        draw_badge_with_overlay(
+            ui,
+            thread.thread_id.get(),
+            cover_rect,
+            "dl_captcha",
+            &crate::localization::translate("card-captcha"),
+            Color32::from_rgb(200, 130, 30),
+            |ui| {
+                ui.set_max_width(250.);
+                ui.add(
+                    egui::Label::new(
+                        RichText::new(crate::localization::translate("card-captcha-text"))
+                            .color(Color32::from_gray(220)),
+                    )
+                    .wrap(true),
+                );
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(crate::localization::translate("card-captcha-open"))
+                        .clicked()
+                    {
+                        app_settings::open_in_browser(prompt.url.as_str());
+                    }
+                    if ui
+                        .button(crate::localization::translate("card-captcha-retry"))
+                        .clicked()
+                    {
+                        prompt.retry();
+                    }
+                });
+            },
+        );
+    }
+
+    // Schedule badge: the download waits for the download window set in Settings
+    if matches!(progress, Some(crate::game_download::Progress::Paused)) {
+        draw_badge_with_overlay(
+            ui,
+            thread.thread_id.get(),
+            cover_rect,
+            "dl_scheduled",
+            &crate::localization::translate("card-scheduled"),
+            Color32::from_rgb(70, 110, 170),
+            |ui| {
+                ui.set_max_width(250.);
+                ui.add(
+                    egui::Label::new(
+                        RichText::new(crate::localization::translate_with(
+                            "card-scheduled-text",
+                            &[("window", crate::game_download::throttle::window_label())],
+                        ))
+                        .color(Color32::from_gray(220)),
+                    )
+                    .wrap(true),
+                );
+            },
+        );
+    }
+
     // Error badge shown when download/unzip error occurs
     if let Some(err) = download_error {
         draw_badge_with_overlay(
@@ -747,7 +887,25 @@ pub fn draw_cover(
         selected_link,
         refresh_clicked: false,
         update_clicked,
+        install_file: None,
+        selected_platform,
+        choose_platform_clicked: false,
+        repair_clicked: false,
+        library_install_clicked: false,
+    }
+}
+
+/// "Windows/Linux: pixeldrain, mega (3)" for the platform prompt
+fn platform_choice_label(pd: &PlatformDownloads) -> String {
+    let platforms = crate::app::settings::store::DownloadPlatform::labels(*pd.platform());
+    let mut hosts: Vec<String> = Vec::new();
+    for link in pd.links() {
+        let host = link.hosting_name();
+        if !host.is_empty() && !hosts.contains(&host) {
+            hosts.push(host);
+        }
     }
+    format!("{platforms}: {} ({})", hosts.join(", "), pd.links().len())
 }
 
 fn draw_unknown_progress_bar(ui: &mut egui::Ui, cover_rect: egui::Rect) {
@@ -0,0 +1,104 @@
+use crate::parser::game_info::link::DownloadLink;
+use eframe::egui::{self, Color32, RichText, Rounding};
+
+/// Per-card popup to paste a download URL when the thread's links can't be parsed.
+/// Returns the parsed link once the user confirms it.
+pub fn draw_download_url_popup(
+    ui: &mut egui::Ui,
+    thread_id: u64,
+    card_rect: egui::Rect,
+) -> Option<DownloadLink> {
+    let popup_id = egui::Id::new(("download_url_open", thread_id));
+    let input_id = egui::Id::new(("download_url_input", thread_id));
+    let is_open = ui
+        .ctx()
+        .memory(|m| m.data.get_temp::<bool>(popup_id))
+        .unwrap_or(false);
+
+    if !is_open {
+        return None;
+    }
+
+    let mut input = ui
+        .ctx()
+        .memory(|m| m.data.get_temp::<String>(input_id))
+        .unwrap_or_default();
+    let mut close = false;
+    let mut result: Option<DownloadLink> = None;
+
+    let popup_width = 320.0;
+    let popup_pos = egui::pos2(
+        card_rect.left(),
+        card_rect.bottom() + crate::ui_constants::spacing::SMALL,
+    );
+
+    let inner = crate::views::ui_helpers::show_popup_area(
+        ui,
+        egui::Id::new(("download_url_area", thread_id)),
+        popup_pos,
+        popup_width,
+        Color32::from_gray(80),
+        Rounding::same(crate::ui_constants::card::ROUNDING),
+        |ui| {
+            ui.set_max_width(popup_width - 16.0);
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+            ui.label(RichText::new(crate::localization::translate("download-url-title")).strong());
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+
+            let resp = ui.add(
+                egui::TextEdit::singleline(&mut input)
+                    .hint_text("https://")
+                    .desired_width(popup_width - 24.0),
+            );
+            let link = DownloadLink::from_user_input(&input);
+            let hint = match &link {
+                _ if input.trim().is_empty() => crate::localization::translate("download-url-hint"),
+                Some(DownloadLink::Http(_)) => {
+                    crate::localization::translate("download-url-plain-http")
+                }
+                Some(_) => crate::localization::translate("download-url-supported"),
+                None if DownloadLink::is_masked_input(&input) => {
+                    crate::localization::translate("download-url-unsupported-hosting")
+                }
+                None => crate::localization::translate("download-url-invalid"),
+            };
+            ui.label(RichText::new(hint).small().color(Color32::from_gray(150)));
+
+            ui.horizontal(|ui| {
+                let submit = resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui
+                    .add_enabled(
+                        link.is_some(),
+                        egui::Button::new(crate::localization::translate("download-url-start")),
+                    )
+                    .clicked()
+                    || (submit && link.is_some())
+                {
+                    result = link;
+                    close = true;
+                }
+                if ui
+                    .button(crate::localization::translate(
+                        "bookmarks-selector-cancel-btn",
+                    ))
+                    .clicked()
+                {
+                    close = true;
+                }
+            });
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+        },
+    );
+
+    let clicked_outside =
+        crate::views::ui_helpers::clicked_outside(ui, &[inner.response.rect, card_rect]);
+    if close || clicked_outside {
+        ui.memory_mut(|m| {
+            m.data.insert_temp(popup_id, false);
+            m.data.remove::<String>(input_id);
+        });
+    } else {
+        ui.memory_mut(|m| m.data.insert_temp(input_id, input));
+    }
+    result
+}
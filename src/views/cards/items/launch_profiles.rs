@@ -0,0 +1,231 @@
+use crate::app::settings::store::{LaunchProfile, get_launch_profiles, set_launch_profiles};
+use eframe::egui::{self, Color32, RichText, Rounding};
+use std::path::PathBuf;
+
+/// Editable copy of a profile; optional paths and env are edited as plain text.
+#[derive(Clone, Default)]
+struct ProfileDraft {
+    name: String,
+    exe_override: String,
+    args: String,
+    env: String,
+    working_dir: String,
+    wrapper: String,
+}
+
+impl From<&LaunchProfile> for ProfileDraft {
+    fn from(p: &LaunchProfile) -> Self {
+        let path_text = |p: &Option<PathBuf>| {
+            p.as_ref()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        Self {
+            name: p.name.clone(),
+            exe_override: path_text(&p.exe_override),
+            args: p.args.clone(),
+            env: p
+                .env
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            working_dir: path_text(&p.working_dir),
+            wrapper: p.wrapper.clone(),
+        }
+    }
+}
+
+impl ProfileDraft {
+    fn to_profile(&self) -> LaunchProfile {
+        let path_opt = |s: &str| {
+            let s = s.trim();
+            (!s.is_empty()).then(|| PathBuf::from(s))
+        };
+        LaunchProfile {
+            name: self.name.trim().to_string(),
+            exe_override: path_opt(&self.exe_override),
+            args: self.args.trim().to_string(),
+            // One KEY=VALUE per line
+            env: self
+                .env
+                .lines()
+                .filter_map(|l| l.split_once('='))
+                .filter(|(k, _)| !k.trim().is_empty())
+                .map(|(k, v)| (k.trim().to_string(), v.to_string()))
+                .collect(),
+            working_dir: path_opt(&self.working_dir),
+            wrapper: self.wrapper.trim().to_string(),
+        }
+    }
+}
+
+/// Per-card popup to create, edit and delete named launch profiles.
+pub fn draw_launch_profiles_popup(ui: &mut egui::Ui, thread_id: u64, card_rect: egui::Rect) {
+    let popup_id = egui::Id::new(("launch_profiles_open", thread_id));
+    let draft_id = egui::Id::new(("launch_profiles_draft", thread_id));
+    let is_open = ui
+        .ctx()
+        .memory(|m| m.data.get_temp::<bool>(popup_id))
+        .unwrap_or(false);
+
+    if !is_open {
+        return;
+    }
+
+    let mut drafts = ui
+        .ctx()
+        .memory(|m| m.data.get_temp::<Vec<ProfileDraft>>(draft_id))
+        .unwrap_or_else(|| {
+            get_launch_profiles(thread_id)
+                .iter()
+                .map(ProfileDraft::from)
+                .collect()
+        });
+    let mut close = false;
+
+    let popup_width = 320.0;
+    let popup_pos = egui::pos2(
+        card_rect.left(),
+        card_rect.bottom() + crate::ui_constants::spacing::SMALL,
+    );
+
+    let inner = crate::views::ui_helpers::show_popup_area(
+        ui,
+        egui::Id::new(("launch_profiles_area", thread_id)),
+        popup_pos,
+        popup_width,
+        Color32::from_gray(80),
+        Rounding::same(crate::ui_constants::card::ROUNDING),
+        |ui| {
+            ui.set_max_width(popup_width - 16.0);
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+            ui.label(
+                RichText::new(crate::localization::translate("launch-profiles-title")).strong(),
+            );
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+
+            let mut remove: Option<usize> = None;
+            egui::ScrollArea::vertical()
+                .max_height(360.0)
+                .show(ui, |ui| {
+                    for (idx, d) in drafts.iter_mut().enumerate() {
+                        ui.group(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut d.name)
+                                        .hint_text(crate::localization::translate(
+                                            "launch-profiles-name",
+                                        ))
+                                        .desired_width(popup_width - 70.0),
+                                );
+                                if ui.button("✕").clicked() {
+                                    remove = Some(idx);
+                                }
+                            });
+                            egui::Grid::new(("launch_profile_grid", thread_id, idx))
+                                .num_columns(2)
+                                .show(ui, |ui| {
+                                    let row =
+                                        |ui: &mut egui::Ui,
+                                         key: &str,
+                                         value: &mut String,
+                                         hint: &str| {
+                                            ui.label(crate::localization::translate(key));
+                                            ui.add(
+                                                egui::TextEdit::singleline(value)
+                                                    .hint_text(hint)
+                                                    .desired_width(190.0),
+                                            );
+                                            ui.end_row();
+                                        };
+                                    row(ui, "launch-profiles-exe", &mut d.exe_override, "game.exe");
+                                    row(
+                                        ui,
+                                        "launch-profiles-args",
+                                        &mut d.args,
+                                        "--mod \"My Mod\"",
+                                    );
+                                    row(
+                                        ui,
+                                        "launch-profiles-wrapper",
+                                        &mut d.wrapper,
+                                        "wine {{path}}",
+                                    );
+                                    row(
+                                        ui,
+                                        "launch-profiles-workdir",
+                                        &mut d.working_dir,
+                                        "{{dir}}",
+                                    );
+                                    ui.label(crate::localization::translate("launch-profiles-env"));
+                                    ui.add(
+                                        egui::TextEdit::multiline(&mut d.env)
+                                            .hint_text("KEY=VALUE")
+                                            .desired_rows(2)
+                                            .desired_width(190.0),
+                                    );
+                                    ui.end_row();
+                                });
+                        });
+                    }
+                });
+            if let Some(idx) = remove {
+                drafts.remove(idx);
+            }
+
+            ui.label(
+                RichText::new(crate::localization::translate(
+                    "launch-profiles-placeholders",
+                ))
+                .small()
+                .color(Color32::from_gray(150)),
+            );
+            ui.horizontal(|ui| {
+                if ui
+                    .button(crate::localization::translate("launch-profiles-add"))
+                    .clicked()
+                {
+                    drafts.push(ProfileDraft {
+                        name: format!("Profile {}", drafts.len() + 1),
+                        ..Default::default()
+                    });
+                }
+                if ui
+                    .button(crate::localization::translate("launch-profiles-save"))
+                    .clicked()
+                {
+                    let mut profiles: Vec<LaunchProfile> = Vec::new();
+                    for p in drafts.iter().map(ProfileDraft::to_profile) {
+                        // Names identify profiles; skip empty and duplicate ones
+                        if !p.name.is_empty() && !profiles.iter().any(|e| e.name == p.name) {
+                            profiles.push(p);
+                        }
+                    }
+                    set_launch_profiles(thread_id, profiles);
+                    close = true;
+                }
+                if ui
+                    .button(crate::localization::translate(
+                        "bookmarks-selector-cancel-btn",
+                    ))
+                    .clicked()
+                {
+                    close = true;
+                }
+            });
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+        },
+    );
+
+    let clicked_outside =
+        crate::views::ui_helpers::clicked_outside(ui, &[inner.response.rect, card_rect]);
+    if close || clicked_outside {
+        ui.memory_mut(|m| {
+            m.data.insert_temp(popup_id, false);
+            m.data.remove::<Vec<ProfileDraft>>(draft_id);
+        });
+    } else {
+        ui.memory_mut(|m| m.data.insert_temp(draft_id, drafts));
+    }
+}
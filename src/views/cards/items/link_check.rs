@@ -0,0 +1,123 @@
+use crate::app::settings::store::DownloadPlatform;
+use crate::game_download::link_check::{LinkCheck, clear_link_check, link_check};
+use crate::parser::game_info::link::{LinkProbe, ProbeStatus, format_size};
+use eframe::egui::{self, Color32, RichText, Rounding};
+
+/// Per-card popup listing every mirror of the thread with its reachability, size and file name.
+/// The check itself is started from the card context menu.
+pub fn draw_link_check_popup(ui: &mut egui::Ui, thread_id: u64, card_rect: egui::Rect) {
+    let popup_id = egui::Id::new(("link_check_open", thread_id));
+    let is_open = ui
+        .ctx()
+        .memory(|m| m.data.get_temp::<bool>(popup_id))
+        .unwrap_or(false);
+
+    if !is_open {
+        return;
+    }
+
+    let mut close = false;
+    let popup_width = 420.0;
+    let popup_pos = egui::pos2(
+        card_rect.left(),
+        card_rect.bottom() + crate::ui_constants::spacing::SMALL,
+    );
+
+    let inner = crate::views::ui_helpers::show_popup_area(
+        ui,
+        egui::Id::new(("link_check_area", thread_id)),
+        popup_pos,
+        popup_width,
+        Color32::from_gray(80),
+        Rounding::same(crate::ui_constants::card::ROUNDING),
+        |ui| {
+            ui.set_max_width(popup_width - 16.0);
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+            ui.label(RichText::new(crate::localization::translate("link-check-title")).strong());
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+
+            match link_check(thread_id) {
+                None | Some(LinkCheck::Running) => {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Spinner::new());
+                        ui.label(crate::localization::translate("link-check-running"));
+                    });
+                }
+                Some(LinkCheck::Failed(err)) => {
+                    ui.colored_label(
+                        Color32::RED,
+                        crate::localization::translate_with("error-prefix", &[("err", err)]),
+                    );
+                }
+                Some(LinkCheck::Done(platforms)) if platforms.is_empty() => {
+                    ui.label(crate::localization::translate("link-check-no-links"));
+                }
+                Some(LinkCheck::Done(platforms)) => {
+                    egui::ScrollArea::vertical()
+                        .max_height(320.0)
+                        .show(ui, |ui| {
+                            for pc in &platforms {
+                                ui.label(
+                                    RichText::new(DownloadPlatform::labels(pc.platform)).strong(),
+                                );
+                                for probe in &pc.links {
+                                    draw_probe_row(ui, probe);
+                                }
+                                ui.add_space(crate::ui_constants::spacing::SMALL);
+                            }
+                        });
+                }
+            }
+
+            if ui
+                .button(crate::localization::translate("link-check-close"))
+                .clicked()
+            {
+                close = true;
+            }
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+        },
+    );
+
+    let clicked_outside =
+        crate::views::ui_helpers::clicked_outside(ui, &[inner.response.rect, card_rect]);
+    if close || clicked_outside {
+        ui.memory_mut(|m| m.data.insert_temp(popup_id, false));
+        clear_link_check(thread_id);
+    }
+}
+
+fn draw_probe_row(ui: &mut egui::Ui, probe: &LinkProbe) {
+    ui.horizontal(|ui| {
+        let (status, color, hover) = match &probe.status {
+            ProbeStatus::Reachable => (
+                crate::localization::translate("link-check-reachable"),
+                Color32::from_rgb(90, 200, 90),
+                None,
+            ),
+            ProbeStatus::Captcha => (
+                crate::localization::translate("link-check-captcha"),
+                Color32::from_rgb(230, 180, 60),
+                None,
+            ),
+            ProbeStatus::Dead(reason) => (
+                crate::localization::translate("link-check-dead"),
+                Color32::from_rgb(220, 80, 80),
+                Some(reason.clone()),
+            ),
+        };
+        let resp = ui.label(RichText::new(status).color(color));
+        if let Some(reason) = hover {
+            resp.on_hover_text(reason);
+        }
+        ui.label(&probe.hosting);
+        let size = probe
+            .size
+            .map(format_size)
+            .unwrap_or_else(|| crate::localization::translate("link-check-unknown-size"));
+        ui.label(RichText::new(size).color(Color32::from_gray(180)));
+        if let Some(name) = &probe.file_name {
+            ui.label(RichText::new(name).small().color(Color32::from_gray(150)));
+        }
+    });
+}
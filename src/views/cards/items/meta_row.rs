@@ -3,7 +3,16 @@ use eframe::egui::{self, Color32, RichText};
 use crate::parser::F95Thread;
 
 /// Draws a single-line meta row: date, likes, views, rating.
+/// For installed games with tracked sessions, playtime is appended (last played on hover).
 pub fn draw_meta_row(ui: &mut egui::Ui, t: &F95Thread) {
+    let playtime = crate::app::settings::with_settings(|st| {
+        st.downloaded_games
+            .iter()
+            .find(|g| g.thread_id == t.thread_id.get())
+            .filter(|g| g.total_playtime_secs > 0)
+            .map(|g| (g.total_playtime_secs, g.last_played))
+    });
+
     ui.horizontal(|ui| {
         ui.spacing_mut().item_spacing.x = crate::ui_constants::spacing::MEDIUM;
         let col = Color32::from_rgb(170, 170, 170);
@@ -16,5 +25,41 @@ pub fn draw_meta_row(ui: &mut egui::Ui, t: &F95Thread) {
                 .small()
                 .color(col),
         );
+        if let Some((secs, last_played)) = playtime {
+            let resp = ui.label(
+                RichText::new(format!("⏱ {}", format_playtime(secs)))
+                    .small()
+                    .color(col),
+            );
+            if let Some(ts) = last_played {
+                resp.on_hover_text(format_last_played(ts));
+            }
+        }
     });
 }
+
+/// "45m", "3h 05m"
+fn format_playtime(secs: u64) -> String {
+    let minutes = secs / 60;
+    if minutes < 60 {
+        format!("{}m", minutes.max(1))
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+fn format_last_played(ts: i64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(ts);
+    let days = (now - ts).max(0) / 86400;
+    if days == 0 {
+        crate::localization::translate("card-last-played-today")
+    } else {
+        crate::localization::translate_with(
+            "card-last-played-days-ago",
+            &[("days", days.to_string())],
+        )
+    }
+}
@@ -4,6 +4,10 @@ pub mod bookmark_selector;
 pub mod card;
 mod cover_helpers;
 mod cover_hover;
+pub mod download_url;
+pub mod launch_profiles;
+pub mod link_check;
 mod meta_row;
 mod tags_panel;
+pub mod verify_files;
 pub use card::thread_card;
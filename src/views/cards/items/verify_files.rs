@@ -0,0 +1,155 @@
+use crate::game_download::integrity::{VerifyState, clear_verify_state, verify_state};
+use eframe::egui::{self, Color32, RichText, Rounding};
+use std::time::Duration;
+
+/// Files listed per group before the rest is summarised
+const MAX_LISTED: usize = 50;
+/// How often a running verify or repair is checked for its result
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Per-card popup with the result of "Verify files". Returns true when Repair was clicked.
+pub fn draw_verify_files_popup(ui: &mut egui::Ui, thread_id: u64, card_rect: egui::Rect) -> bool {
+    let popup_id = egui::Id::new(("verify_files_open", thread_id));
+    let mut is_open = ui
+        .ctx()
+        .memory(|m| m.data.get_temp::<bool>(popup_id))
+        .unwrap_or(false);
+
+    // Results come from background threads: poll while they run, and show a repair's
+    // outcome even if the popup was closed during the download
+    match verify_state(thread_id) {
+        Some(VerifyState::Running | VerifyState::Repairing) => {
+            ui.ctx().request_repaint_after(POLL_INTERVAL);
+        }
+        Some(VerifyState::Repaired(_) | VerifyState::Failed(_)) if !is_open => {
+            ui.memory_mut(|m| m.data.insert_temp(popup_id, true));
+            is_open = true;
+        }
+        _ => {}
+    }
+
+    if !is_open {
+        return false;
+    }
+
+    let mut close = false;
+    let mut repair = false;
+    let popup_width = 380.0;
+    let popup_pos = egui::pos2(
+        card_rect.left(),
+        card_rect.bottom() + crate::ui_constants::spacing::SMALL,
+    );
+
+    let inner = crate::views::ui_helpers::show_popup_area(
+        ui,
+        egui::Id::new(("verify_files_area", thread_id)),
+        popup_pos,
+        popup_width,
+        Color32::from_gray(80),
+        Rounding::same(crate::ui_constants::card::ROUNDING),
+        |ui| {
+            ui.set_max_width(popup_width - 16.0);
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+            ui.label(RichText::new(crate::localization::translate("verify-title")).strong());
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+
+            match verify_state(thread_id) {
+                None | Some(VerifyState::Running) => {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Spinner::new());
+                        ui.label(crate::localization::translate("verify-running"));
+                    });
+                }
+                Some(VerifyState::NoManifest) => {
+                    ui.label(crate::localization::translate("verify-no-manifest"));
+                }
+                Some(VerifyState::Done(report)) if report.is_ok() => {
+                    ui.colored_label(
+                        Color32::from_rgb(90, 200, 90),
+                        crate::localization::translate_with(
+                            "verify-ok",
+                            &[("count", report.checked.to_string())],
+                        ),
+                    );
+                }
+                Some(VerifyState::Done(report)) => {
+                    egui::ScrollArea::vertical()
+                        .max_height(240.0)
+                        .show(ui, |ui| {
+                            for (key, files) in [
+                                ("verify-missing", &report.missing),
+                                ("verify-changed", &report.changed),
+                            ] {
+                                if files.is_empty() {
+                                    continue;
+                                }
+                                ui.label(
+                                    RichText::new(crate::localization::translate_with(
+                                        key,
+                                        &[("count", files.len().to_string())],
+                                    ))
+                                    .color(Color32::from_rgb(220, 80, 80)),
+                                );
+                                for f in files.iter().take(MAX_LISTED) {
+                                    ui.label(RichText::new(f).small());
+                                }
+                                if files.len() > MAX_LISTED {
+                                    ui.label(
+                                        RichText::new(format!("… +{}", files.len() - MAX_LISTED))
+                                            .small(),
+                                    );
+                                }
+                            }
+                        });
+                    if ui
+                        .button(crate::localization::translate("verify-repair"))
+                        .on_hover_text(crate::localization::translate("verify-repair-hint"))
+                        .clicked()
+                    {
+                        repair = true;
+                    }
+                }
+                Some(VerifyState::Repairing) => {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Spinner::new());
+                        ui.label(crate::localization::translate("verify-repairing"));
+                    });
+                }
+                Some(VerifyState::Repaired(count)) => {
+                    ui.colored_label(
+                        Color32::from_rgb(90, 200, 90),
+                        crate::localization::translate_with(
+                            "verify-repaired",
+                            &[("count", count.to_string())],
+                        ),
+                    );
+                }
+                Some(VerifyState::Failed(err)) => {
+                    ui.colored_label(
+                        Color32::RED,
+                        crate::localization::translate_with("error-prefix", &[("err", err)]),
+                    );
+                }
+            }
+
+            if ui
+                .button(crate::localization::translate("link-check-close"))
+                .clicked()
+            {
+                close = true;
+            }
+            ui.add_space(crate::ui_constants::spacing::SMALL);
+        },
+    );
+
+    let clicked_outside =
+        crate::views::ui_helpers::clicked_outside(ui, &[inner.response.rect, card_rect]);
+    if close || clicked_outside {
+        ui.memory_mut(|m| m.data.insert_temp(popup_id, false));
+        // Keep the state of a running repair so reopening shows it
+        if !matches!(verify_state(thread_id), Some(VerifyState::Repairing)) {
+            clear_verify_state(thread_id);
+        }
+    }
+    repair
+}
@@ -37,6 +37,7 @@ where
 pub fn draw_filters_panel(
     ctx: &egui::Context,
     sort: &mut Sorting,
+    library_sort: &mut LibrarySorting,
     date_limit: &mut DateLimit,
     include_logic: &mut TagLogic,
     include_tags: &mut Vec<u32>,
@@ -49,11 +50,12 @@ pub fn draw_filters_panel(
     filter_bookmarks: &mut Vec<String>,
     library_only: &mut bool,
     unplayed_only: &mut bool,
-) -> (bool, bool, bool, bool) {
+) -> (bool, bool, bool, bool, bool) {
     let mut changed_now: bool = false;
     let mut settings_clicked: bool = false;
     let mut logs_clicked: bool = false;
     let mut about_clicked: bool = false;
+    let mut downloads_clicked: bool = false;
     egui::SidePanel::right("filters_panel")
         .frame(
             egui::Frame::none()
@@ -65,8 +67,13 @@ pub fn draw_filters_panel(
             ui.label(RichText::new(crate::localization::translate("filters-title")).strong());
             ui.separator();
 
-            // SORTING
-            if segmented_panel(ui, "filters-sorting", sort) {
+            // SORTING (Library view is ordered client-side by its own keys)
+            let sort_changed = if *library_only {
+                segmented_panel(ui, "filters-sorting", library_sort)
+            } else {
+                segmented_panel(ui, "filters-sorting", sort)
+            };
+            if sort_changed {
                 changed_now = true;
             }
 
@@ -275,6 +282,12 @@ pub fn draw_filters_panel(
                 {
                     logs_clicked = true;
                 }
+                if ui
+                    .button(crate::localization::translate("common-downloads"))
+                    .clicked()
+                {
+                    downloads_clicked = true;
+                }
                 if ui
                     .button(crate::localization::translate("common-about"))
                     .clicked()
@@ -322,5 +335,11 @@ pub fn draw_filters_panel(
             });
         });
 
-    (changed_now, settings_clicked, logs_clicked, about_clicked)
+    (
+        changed_now,
+        settings_clicked,
+        logs_clicked,
+        about_clicked,
+        downloads_clicked,
+    )
 }